@@ -1,7 +1,11 @@
+use std::rc::Rc;
+
+use crate::ast::stmt::Stmt;
 use crate::token::{LiteralType, Token};
 
 /// Enum of lox's expression.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expr {
     Assignment {
         name: Token,
@@ -12,6 +16,11 @@ pub enum Expr {
         operator: Token,
         right: Box<Expr>,
     },
+    Call {
+        callee: Box<Expr>,
+        paren: Token,
+        arguments: Vec<Expr>,
+    },
     Grouping {
         expression: Box<Expr>,
     },
@@ -30,16 +39,66 @@ pub enum Expr {
     Variable {
         name: Token,
     },
+    /// List literal such as `[1, 2]`.
+    List {
+        bracket: Token,
+        elements: Vec<Expr>,
+    },
+    /// Map literal such as `{"a": 1}`.
+    Map {
+        brace: Token,
+        entries: Vec<(Expr, Expr)>,
+    },
+    /// Element read such as `xs[0]`.
+    Index {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+    },
+    /// Element write such as `xs[0] = 1`.
+    IndexSet {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+        value: Box<Expr>,
+    },
+    /// Compound assignment such as `a += 1` or `xs[0] *= 2`, to a variable or index.
+    CompoundAssignment {
+        target: Box<Expr>,
+        operator: Token,
+        value: Box<Expr>,
+    },
+    /// Increment or decrement of a variable or index, such as `++a` or `xs[0]--`.
+    Update {
+        operator: Token,
+        target: Box<Expr>,
+        prefix: bool,
+    },
+    /// Anonymous function such as `fun (a) { return a; }` or `(a) => a`, with its
+    /// `fun` or `=>` token. The body is a block, or a `return` of the arrow's expression.
+    Lambda {
+        keyword: Token,
+        params: Vec<Token>,
+        body: Rc<Stmt>,
+    },
 }
 
 pub trait Visitor<T> {
     fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> T;
     fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> T;
+    fn visit_call(&mut self, callee: &Expr, paren: &Token, arguments: &[Expr]) -> T;
     fn visit_grouping(&mut self, expression: &Expr) -> T;
     fn visit_literal(&mut self, value: &LiteralType) -> T;
     fn visit_unary(&mut self, operator: &Token, right: &Expr) -> T;
     fn visit_ternary(&mut self, condition: &Expr, truepart: &Expr, falsepart: &Expr) -> T;
     fn visit_variable(&mut self, name: &Token) -> T;
+    fn visit_list(&mut self, bracket: &Token, elements: &[Expr]) -> T;
+    fn visit_map(&mut self, brace: &Token, entries: &[(Expr, Expr)]) -> T;
+    fn visit_index(&mut self, object: &Expr, bracket: &Token, index: &Expr) -> T;
+    fn visit_index_set(&mut self, object: &Expr, bracket: &Token, index: &Expr, value: &Expr) -> T;
+    fn visit_compound_assignment(&mut self, target: &Expr, operator: &Token, value: &Expr) -> T;
+    fn visit_update(&mut self, operator: &Token, target: &Expr, prefix: bool) -> T;
+    fn visit_lambda(&mut self, keyword: &Token, params: &[Token], body: &Rc<Stmt>) -> T;
 }
 
 impl Expr {
@@ -54,6 +113,11 @@ impl Expr {
                 operator,
                 right,
             } => visitor.visit_binary_expr(left, operator, right),
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            } => visitor.visit_call(callee, paren, arguments),
             Expr::Grouping { expression } => visitor.visit_grouping(expression),
             Expr::Literal { value } => visitor.visit_literal(value),
             Expr::Unary { operator, right } => visitor.visit_unary(operator, right),
@@ -63,6 +127,54 @@ impl Expr {
                 falsepart,
             } => visitor.visit_ternary(condition, truepart, falsepart),
             Expr::Variable { name } => visitor.visit_variable(name),
+            Expr::List { bracket, elements } => visitor.visit_list(bracket, elements),
+            Expr::Map { brace, entries } => visitor.visit_map(brace, entries),
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => visitor.visit_index(object, bracket, index),
+            Expr::IndexSet {
+                object,
+                bracket,
+                index,
+                value,
+            } => visitor.visit_index_set(object, bracket, index, value),
+            Expr::CompoundAssignment {
+                target,
+                operator,
+                value,
+            } => visitor.visit_compound_assignment(target, operator, value),
+            Expr::Update {
+                operator,
+                target,
+                prefix,
+            } => visitor.visit_update(operator, target, *prefix),
+            Expr::Lambda {
+                keyword,
+                params,
+                body,
+            } => visitor.visit_lambda(keyword, params, body),
+        }
+    }
+
+    /// Source line of the expression, unknown for literals which keep no token.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Expr::Assignment { name, .. } | Expr::Variable { name } => Some(name.line),
+            Expr::Binary { operator, .. }
+            | Expr::Unary { operator, .. }
+            | Expr::CompoundAssignment { operator, .. }
+            | Expr::Update { operator, .. } => Some(operator.line),
+            Expr::Call { paren, .. } => Some(paren.line),
+            Expr::List { bracket, .. }
+            | Expr::Index { bracket, .. }
+            | Expr::IndexSet { bracket, .. } => Some(bracket.line),
+            Expr::Map { brace, .. } => Some(brace.line),
+            Expr::Lambda { keyword, .. } => Some(keyword.line),
+            Expr::Grouping { expression } => expression.line(),
+            Expr::Ternary { condition, .. } => condition.line(),
+            Expr::Literal { .. } => None,
         }
     }
 }
@@ -0,0 +1,526 @@
+use std::{collections::VecDeque, rc::Rc};
+
+use crate::{
+    ast::{
+        expr::{self, Expr},
+        format_float, quote,
+        stmt::{self, Stmt},
+    },
+    bignum::Decimal,
+    scanner::Comment,
+    token::{LiteralType, Token, TokenType},
+};
+
+/// Indentation of one block level.
+const INDENT: &str = "  ";
+
+// Binding power of expressions, from loosest to tightest.
+const ASSIGNMENT: u8 = 1;
+const TERNARY: u8 = 2;
+const EQUALITY: u8 = 3;
+const COMPARISON: u8 = 4;
+const RANGE: u8 = 5;
+const BIT_OR: u8 = 6;
+const BIT_XOR: u8 = 7;
+const BIT_AND: u8 = 8;
+const SHIFT: u8 = 9;
+const TERM: u8 = 10;
+const FACTOR: u8 = 11;
+const UNARY: u8 = 12;
+const POWER: u8 = 13;
+const UPDATE: u8 = 14;
+const CALL: u8 = 15;
+const PRIMARY: u8 = 16;
+
+/// Print a program back as canonical lox source.
+///
+/// Redundant parentheses are dropped, blocks are indented and comments
+/// collected by the `Scanner` are placed back by line.
+pub struct Formatter {
+    comments: VecDeque<Comment>,
+    out: String,
+    depth: usize,
+    /// Source line of the last emitted statement or comment.
+    last_line: usize,
+    /// Whether nothing has been emitted yet in current block.
+    block_start: bool,
+    /// Start and end line of the statement being formatted.
+    line: usize,
+    end_line: usize,
+}
+
+impl Formatter {
+    pub fn new(comments: Vec<Comment>) -> Self {
+        Self {
+            comments: comments.into(),
+            out: String::new(),
+            depth: 0,
+            last_line: 0,
+            block_start: true,
+            line: 0,
+            end_line: 0,
+        }
+    }
+
+    /// Format a given program into source code.
+    pub fn format(mut self, program: &Stmt) -> String {
+        program.accept(&mut self);
+        self.out
+    }
+
+    /// Format statements of a program or block.
+    fn statements(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            self.leading_comments(stmt.line());
+            self.separate(stmt.line());
+            self.line = stmt.line();
+            self.end_line = stmt.end_line();
+            stmt.accept(self);
+            // A function body may end past the line where its statement ends.
+            self.last_line = self.last_line.max(stmt.end_line());
+        }
+    }
+
+    /// Emit comments before the given line on their own lines.
+    fn leading_comments(&mut self, line: usize) {
+        while let Some(comment) = self.comments.front() {
+            if comment.line >= line {
+                break;
+            }
+            let comment = self.comments.pop_front().unwrap();
+            self.separate(comment.line);
+            self.write_line(&comment.text);
+            self.last_line = comment.line;
+        }
+    }
+
+    /// Return the comment at the end of the given line, if any.
+    fn trailing_comment(&mut self, line: usize) -> String {
+        match self.comments.front() {
+            Some(comment) if comment.line == line => {
+                format!(" {}", self.comments.pop_front().unwrap().text)
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Keep one blank line where source had one or more.
+    fn separate(&mut self, line: usize) {
+        if !self.block_start && line > self.last_line + 1 {
+            self.out.push('\n');
+        }
+        self.block_start = false;
+    }
+
+    fn write_line(&mut self, text: &str) {
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// Write a statement fitting on a single line.
+    fn simple_statement(&mut self, text: String) {
+        let trailing = self.trailing_comment(self.line);
+        self.write_line(&format!("{text}{trailing}"));
+    }
+
+    /// Write a block, optionally preceded by a header such as `test "name"`.
+    fn block(&mut self, header: &str, statements: &[Stmt], line: usize, end_line: usize) {
+        if self.open_block(header, statements, line, end_line) {
+            let trailing = self.trailing_comment(end_line);
+            self.write_line(&format!("}}{trailing}"));
+        }
+    }
+
+    /// Write a block up to its closing `}`, returning whether it is left to write.
+    /// An empty block is written whole as `{}`.
+    fn open_block(
+        &mut self,
+        header: &str,
+        statements: &[Stmt],
+        line: usize,
+        end_line: usize,
+    ) -> bool {
+        let has_comments = self.comments.front().is_some_and(|c| c.line <= end_line);
+        if statements.is_empty() && !has_comments {
+            self.write_line(&format!("{header}{{}}"));
+            return false;
+        }
+
+        let trailing = self.trailing_comment(line);
+        self.write_line(&format!("{header}{{{trailing}"));
+        self.depth += 1;
+        self.block_start = true;
+        self.last_line = line;
+        self.statements(statements);
+        self.leading_comments(end_line);
+        self.depth -= 1;
+        true
+    }
+
+    /// Write the body of a statement after its header, on the same line for a block,
+    /// indented on the next line otherwise.
+    fn body(&mut self, header: &str, body: &Stmt) {
+        if let Stmt::Block(statements, line, end_line) = body {
+            self.block(header, statements, *line, *end_line);
+            return;
+        }
+        // A comment on the line of both header and body belongs to the body.
+        let trailing = if body.line() == self.line {
+            String::new()
+        } else {
+            self.trailing_comment(self.line)
+        };
+        self.write_line(&format!("{}{}", header.trim_end(), trailing));
+        self.depth += 1;
+        self.block_start = true;
+        self.statements(std::slice::from_ref(body));
+        self.depth -= 1;
+    }
+
+    /// Write an `if` statement, its header preceded by `prefix` such as `} else `.
+    fn if_statement(
+        &mut self,
+        prefix: &str,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: Option<&Stmt>,
+    ) {
+        let header = format!("{prefix}if ({}) ", self.operand(condition, ASSIGNMENT));
+        self.body(&header, then_branch);
+        let Some(else_branch) = else_branch else {
+            return;
+        };
+
+        // Keep `else` on the line closing a block.
+        let mut prefix = String::new();
+        if matches!(then_branch, Stmt::Block(..)) {
+            let start = self.out[..self.out.len() - 1]
+                .rfind('\n')
+                .map_or(0, |i| i + 1);
+            let last = self.out[start..].trim();
+            if last == "}" || last.ends_with("{}") {
+                prefix = format!("{last} ");
+                self.out.truncate(start);
+            }
+        }
+        prefix.push_str("else ");
+        self.line = else_branch.line();
+        match else_branch {
+            Stmt::If(condition, then_branch, else_branch, _) => {
+                self.if_statement(&prefix, condition, then_branch, else_branch.as_deref())
+            }
+            _ => self.body(&prefix, else_branch),
+        }
+    }
+
+    /// Format an operand, parenthesized only if it binds looser than `min`.
+    fn operand(&mut self, expr: &Expr, min: u8) -> String {
+        let mut inner = expr;
+        while let Expr::Grouping { expression } = inner {
+            inner = expression;
+        }
+        let s = inner.accept(self);
+        if precedence(inner) < min {
+            format!("({s})")
+        } else {
+            s
+        }
+    }
+}
+
+/// Binding power of an expression.
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Assignment { .. } => ASSIGNMENT,
+        Expr::Ternary { .. } => TERNARY,
+        Expr::Binary { operator, .. } => binary_precedence(operator),
+        Expr::Unary { .. } => UNARY,
+        Expr::Call { .. } | Expr::Index { .. } => CALL,
+        Expr::IndexSet { .. } | Expr::CompoundAssignment { .. } => ASSIGNMENT,
+        Expr::Update { .. } => UPDATE,
+        // The expression of an arrow function extends as far right as possible.
+        Expr::Lambda { keyword, .. } if keyword.token_type == TokenType::Arrow => ASSIGNMENT,
+        Expr::Grouping { expression } => precedence(expression),
+        // A negative number reads back as a unary minus.
+        Expr::Literal { value } if is_negative(value) => UNARY,
+        Expr::Literal { .. }
+        | Expr::Variable { .. }
+        | Expr::List { .. }
+        | Expr::Map { .. }
+        | Expr::Lambda { .. } => PRIMARY,
+    }
+}
+
+fn is_negative(value: &LiteralType) -> bool {
+    match value {
+        LiteralType::Int(n) => *n < 0,
+        LiteralType::BigInt(n) => n.is_negative(),
+        LiteralType::Decimal(n) => n < &Decimal::from(0),
+        LiteralType::Number(n) => n.is_sign_negative(),
+        _ => false,
+    }
+}
+
+fn binary_precedence(operator: &Token) -> u8 {
+    match operator.token_type {
+        TokenType::EqualEqual | TokenType::BangEqual => EQUALITY,
+        TokenType::Less | TokenType::LessEqual | TokenType::Greater | TokenType::GreaterEqual => {
+            COMPARISON
+        }
+        TokenType::DotDot => RANGE,
+        TokenType::Pipe => BIT_OR,
+        TokenType::Caret => BIT_XOR,
+        TokenType::Ampersand => BIT_AND,
+        TokenType::LessLess | TokenType::GreaterGreater => SHIFT,
+        TokenType::StarStar => POWER,
+        TokenType::Plus | TokenType::Minus => TERM,
+        _ => FACTOR,
+    }
+}
+
+impl expr::Visitor<String> for Formatter {
+    fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> String {
+        format!("{} = {}", name.lexeme, self.operand(value, ASSIGNMENT))
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> String {
+        let precedence = binary_precedence(operator);
+        let (left_min, right_min, space) = match operator.token_type {
+            // Ranges don't chain, a range as start is parenthesized too, and are written tight.
+            TokenType::DotDot => (precedence + 1, precedence + 1, ""),
+            // Powers associate to the right, where a unary operand needs no parentheses.
+            TokenType::StarStar => (precedence + 1, UNARY, " "),
+            _ => (precedence, precedence + 1, " "),
+        };
+        format!(
+            "{}{space}{}{space}{}",
+            self.operand(left, left_min),
+            operator.lexeme,
+            self.operand(right, right_min)
+        )
+    }
+
+    fn visit_call(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> String {
+        let callee = self.operand(callee, CALL);
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|argument| self.operand(argument, ASSIGNMENT))
+            .collect();
+        format!("{}({})", callee, arguments.join(", "))
+    }
+
+    fn visit_grouping(&mut self, expression: &Expr) -> String {
+        self.operand(expression, ASSIGNMENT)
+    }
+
+    fn visit_literal(&mut self, value: &LiteralType) -> String {
+        match value {
+            LiteralType::Int(n) => n.to_string(),
+            LiteralType::BigInt(n) => format!("{n}n"),
+            LiteralType::Decimal(n) => format!("{n}d"),
+            LiteralType::Number(n) => format_float(*n),
+            LiteralType::String(s) => quote(s),
+            LiteralType::Bool(b) => b.to_string(),
+            LiteralType::Nil => "nil".to_string(),
+        }
+    }
+
+    fn visit_unary(&mut self, operator: &Token, right: &Expr) -> String {
+        let right = self.operand(right, UNARY);
+        // Keep `- -a` and `- --a` from being read back as a decrement.
+        let space = if operator.token_type == TokenType::Minus && right.starts_with('-') {
+            " "
+        } else {
+            ""
+        };
+        format!("{}{space}{right}", operator.lexeme)
+    }
+
+    fn visit_ternary(&mut self, condition: &Expr, truepart: &Expr, falsepart: &Expr) -> String {
+        format!(
+            "{} ? {} : {}",
+            self.operand(condition, EQUALITY),
+            self.operand(truepart, ASSIGNMENT),
+            self.operand(falsepart, ASSIGNMENT)
+        )
+    }
+
+    fn visit_variable(&mut self, name: &Token) -> String {
+        name.lexeme.clone()
+    }
+
+    fn visit_list(&mut self, _bracket: &Token, elements: &[Expr]) -> String {
+        let elements: Vec<String> = elements
+            .iter()
+            .map(|element| self.operand(element, ASSIGNMENT))
+            .collect();
+        format!("[{}]", elements.join(", "))
+    }
+
+    fn visit_map(&mut self, _brace: &Token, entries: &[(Expr, Expr)]) -> String {
+        let entries: Vec<String> = entries
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}: {}",
+                    self.operand(key, ASSIGNMENT),
+                    self.operand(value, ASSIGNMENT)
+                )
+            })
+            .collect();
+        format!("{{{}}}", entries.join(", "))
+    }
+
+    fn visit_index(&mut self, object: &Expr, _bracket: &Token, index: &Expr) -> String {
+        format!(
+            "{}[{}]",
+            self.operand(object, CALL),
+            self.operand(index, ASSIGNMENT)
+        )
+    }
+
+    fn visit_index_set(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> String {
+        format!(
+            "{}[{}] = {}",
+            self.operand(object, CALL),
+            self.operand(index, ASSIGNMENT),
+            self.operand(value, ASSIGNMENT)
+        )
+    }
+
+    fn visit_compound_assignment(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> String {
+        format!(
+            "{} {} {}",
+            self.operand(target, CALL),
+            operator.lexeme,
+            self.operand(value, ASSIGNMENT)
+        )
+    }
+
+    fn visit_update(&mut self, operator: &Token, target: &Expr, prefix: bool) -> String {
+        let target = self.operand(target, CALL);
+        if prefix {
+            format!("{}{target}", operator.lexeme)
+        } else {
+            format!("{target}{}", operator.lexeme)
+        }
+    }
+
+    fn visit_lambda(&mut self, keyword: &Token, params: &[Token], body: &Rc<Stmt>) -> String {
+        let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+        let params = params.join(", ");
+        let (statements, line, end_line) = match body.as_ref() {
+            Stmt::Return(_, Some(value)) if keyword.token_type == TokenType::Arrow => {
+                return format!("({params}) => {}", self.operand(value, ASSIGNMENT));
+            }
+            Stmt::Block(statements, line, end_line) => (statements, *line, *end_line),
+            _ => unreachable!("body of a `fun` lambda is a block"),
+        };
+
+        // Write the body aside, its lines being indented one level deeper than
+        // the statement holding the lambda.
+        let out = std::mem::take(&mut self.out);
+        let statement = (self.line, self.end_line);
+        if self.open_block(&format!("fun ({params}) "), statements, line, end_line) {
+            self.write_line("}");
+        }
+        let text = std::mem::replace(&mut self.out, out);
+        // Comments at the end of the closing line go after the holding statement.
+        (self.line, self.end_line) = (end_line, statement.1.max(end_line));
+        self.last_line = end_line;
+        text.trim().to_owned()
+    }
+}
+
+impl stmt::Visitor<()> for Formatter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) {
+        self.block("", statements, self.line, self.end_line);
+    }
+
+    fn visit_program_stmt(&mut self, declarations: &[Stmt]) {
+        self.statements(declarations);
+        self.leading_comments(usize::MAX);
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: &Option<Expr>) {
+        let text = match initializer {
+            Some(expr) => format!("var {} = {};", name.lexeme, self.operand(expr, ASSIGNMENT)),
+            None => format!("var {};", name.lexeme),
+        };
+        self.simple_statement(text);
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) {
+        let mut text = self.operand(expression, ASSIGNMENT);
+        // Keep a statement starting with a map from being read as a block.
+        if text.starts_with('{') {
+            text = format!("({text})");
+        }
+        let text = format!("{text};");
+        self.simple_statement(text);
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) {
+        let text = format!("print {};", self.operand(expression, ASSIGNMENT));
+        self.simple_statement(text);
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) {
+        let header = format!("while ({}) ", self.operand(condition, ASSIGNMENT));
+        self.body(&header, body);
+    }
+
+    fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) {
+        let header = format!(
+            "for (var {} in {}) ",
+            name.lexeme,
+            self.operand(iterable, ASSIGNMENT)
+        );
+        self.body(&header, body);
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) {
+        self.if_statement("", condition, then_branch, else_branch);
+    }
+
+    fn visit_break_stmt(&mut self, _keyword: &Token) {
+        self.simple_statement("break;".to_owned());
+    }
+
+    fn visit_continue_stmt(&mut self, _keyword: &Token) {
+        self.simple_statement("continue;".to_owned());
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: &Option<Expr>) {
+        let text = match value {
+            Some(value) => format!("return {};", self.operand(value, ASSIGNMENT)),
+            None => "return;".to_owned(),
+        };
+        self.simple_statement(text);
+    }
+
+    fn visit_test_stmt(&mut self, name: &Token, body: &Stmt) {
+        if let Stmt::Block(statements, line, end_line) = body {
+            self.block(
+                &format!("test {} ", name.lexeme),
+                statements,
+                *line,
+                *end_line,
+            );
+        }
+    }
+}
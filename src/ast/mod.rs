@@ -1,3 +1,32 @@
 pub mod expr;
+pub mod formatter;
 pub mod pretty_printer;
+pub mod reader;
 pub mod stmt;
+
+/// Quote and escape a string literal the way it is written in lox source.
+pub(crate) fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Format a float so that it reads back as one, `1.0` rather than `1`.
+pub(crate) fn format_float(n: f64) -> String {
+    if n.is_finite() && n.fract() == 0.0 {
+        format!("{n:.1}")
+    } else {
+        n.to_string()
+    }
+}
@@ -1,9 +1,12 @@
+use std::rc::Rc;
+
 use crate::{
     ast::{
         expr::{self, Expr},
+        format_float, quote,
         stmt::{self, Stmt},
     },
-    token::LiteralType,
+    token::{LiteralType, Token, TokenType},
 };
 
 pub struct AstPrinter();
@@ -14,6 +17,11 @@ impl AstPrinter {
         expr.accept(self)
     }
 
+    /// Format a given `Stmt` to a readable string.
+    pub fn fmt_stmt(&mut self, stmt: &Stmt) -> String {
+        stmt.accept(self)
+    }
+
     /// Parenthesize the given expression list.
     fn parenthesize(&mut self, name: &str, exprs: Vec<&Expr>) -> String {
         let mut s = String::new();
@@ -64,14 +72,28 @@ impl expr::Visitor<String> for AstPrinter {
         self.parenthesize(&operator.lexeme, vec![left, right])
     }
 
+    fn visit_call(
+        &mut self,
+        callee: &Expr,
+        _paren: &crate::token::Token,
+        arguments: &[Expr],
+    ) -> String {
+        let mut exprs = vec![callee];
+        exprs.extend(arguments);
+        self.parenthesize("call", exprs)
+    }
+
     fn visit_grouping(&mut self, expression: &expr::Expr) -> String {
         self.parenthesize("group", vec![expression])
     }
 
     fn visit_literal(&mut self, value: &crate::token::LiteralType) -> String {
         match value {
-            LiteralType::Number(n) => n.to_string(),
-            LiteralType::String(s) => s.to_owned(),
+            LiteralType::Int(n) => n.to_string(),
+            LiteralType::BigInt(n) => format!("{n}n"),
+            LiteralType::Decimal(n) => format!("{n}d"),
+            LiteralType::Number(n) => format_float(*n),
+            LiteralType::String(s) => quote(s),
             LiteralType::Bool(b) => b.to_string(),
             LiteralType::Nil => "nil".to_string(),
         }
@@ -88,6 +110,62 @@ impl expr::Visitor<String> for AstPrinter {
     fn visit_variable(&mut self, name: &crate::token::Token) -> String {
         name.lexeme.clone()
     }
+
+    fn visit_list(&mut self, _bracket: &crate::token::Token, elements: &[Expr]) -> String {
+        self.parenthesize("list", elements.iter().collect())
+    }
+
+    fn visit_map(&mut self, _brace: &crate::token::Token, entries: &[(Expr, Expr)]) -> String {
+        let exprs = entries.iter().flat_map(|(key, value)| [key, value]);
+        self.parenthesize("map", exprs.collect())
+    }
+
+    fn visit_index(
+        &mut self,
+        object: &Expr,
+        _bracket: &crate::token::Token,
+        index: &Expr,
+    ) -> String {
+        self.parenthesize("index", vec![object, index])
+    }
+
+    fn visit_index_set(
+        &mut self,
+        object: &Expr,
+        _bracket: &crate::token::Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> String {
+        self.parenthesize("index=", vec![object, index, value])
+    }
+
+    fn visit_compound_assignment(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> String {
+        self.parenthesize(&operator.lexeme, vec![target, value])
+    }
+
+    fn visit_update(&mut self, operator: &Token, target: &Expr, prefix: bool) -> String {
+        if prefix {
+            self.parenthesize(&operator.lexeme, vec![target])
+        } else {
+            self.parenthesize(&format!("post{}", operator.lexeme), vec![target])
+        }
+    }
+
+    fn visit_lambda(&mut self, keyword: &Token, params: &[Token], body: &Rc<Stmt>) -> String {
+        let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+        let body = match body.as_ref() {
+            Stmt::Return(_, Some(value)) if keyword.token_type == TokenType::Arrow => {
+                value.accept(self)
+            }
+            body => body.accept(self),
+        };
+        format!("({} ({}) {})", keyword.lexeme, params.join(" "), body)
+    }
 }
 
 impl stmt::Visitor<String> for AstPrinter {
@@ -107,6 +185,72 @@ impl stmt::Visitor<String> for AstPrinter {
         self.block(declarations)
     }
 
+    fn visit_test_stmt(&mut self, name: &crate::token::Token, body: &Stmt) -> String {
+        let mut s = String::new();
+        s.push_str("(test ");
+        s.push_str(&name.lexeme);
+        s.push(' ');
+        s.push_str(&body.accept(self));
+        s.push(')');
+        s
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> String {
+        let mut s = String::new();
+        s.push_str("(while ");
+        s.push_str(&condition.accept(self));
+        s.push(' ');
+        s.push_str(&body.accept(self));
+        s.push(')');
+        s
+    }
+
+    fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) -> String {
+        let mut s = String::new();
+        s.push_str("(for ");
+        s.push_str(&name.lexeme);
+        s.push(' ');
+        s.push_str(&iterable.accept(self));
+        s.push(' ');
+        s.push_str(&body.accept(self));
+        s.push(')');
+        s
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: Option<&Stmt>,
+    ) -> String {
+        let mut s = String::new();
+        s.push_str("(if ");
+        s.push_str(&condition.accept(self));
+        s.push(' ');
+        s.push_str(&then_branch.accept(self));
+        if let Some(else_branch) = else_branch {
+            s.push(' ');
+            s.push_str(&else_branch.accept(self));
+        }
+        s.push(')');
+        s
+    }
+
+    fn visit_break_stmt(&mut self, _keyword: &crate::token::Token) -> String {
+        "(break)".to_owned()
+    }
+
+    fn visit_continue_stmt(&mut self, _keyword: &crate::token::Token) -> String {
+        "(continue)".to_owned()
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: &Option<Expr>) -> String {
+        match value {
+            Some(value) => self.parenthesize("return", vec![value]),
+            None => "(return)".to_owned(),
+        }
+    }
+
     fn visit_var_stmt(&mut self, name: &crate::token::Token, initializer: &Option<Expr>) -> String {
         let mut s = String::new();
         s.push_str("(var ");
@@ -0,0 +1,525 @@
+use std::rc::Rc;
+
+use unescape::unescape;
+
+use crate::{
+    ast::{expr::Expr, stmt::Stmt},
+    error::RloxError,
+    token::{LiteralType, Token, TokenType},
+};
+
+/// Lexical element of the S-expression syntax.
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Semicolon,
+    /// Raw content of a quoted string, escape sequences are kept as is.
+    Quoted(String),
+    Symbol(String),
+}
+
+/// Read the S-expression syntax produced by `AstPrinter` back into an AST.
+///
+/// The syntax carries no source positions, so every token and statement
+/// read gets line 0.
+pub struct AstReader {
+    atoms: Vec<(Atom, usize)>,
+    current: usize,
+}
+
+impl AstReader {
+    pub fn new(source: &str) -> Result<Self, RloxError> {
+        Ok(Self {
+            atoms: tokenize(source)?,
+            current: 0,
+        })
+    }
+
+    /// Read a single expression such as `(+ 1 (group 2))`.
+    pub fn read_expr(source: &str) -> Result<Expr, RloxError> {
+        let mut reader = Self::new(source)?;
+        let expr = reader.expr()?;
+        reader.end()?;
+        Ok(expr)
+    }
+
+    /// Read a program such as `[(var a = 1);(print a)]`.
+    pub fn read_program(source: &str) -> Result<Stmt, RloxError> {
+        let mut reader = Self::new(source)?;
+        reader.expect(Atom::LeftBracket, "Expect '[' at program start.")?;
+        let program = Stmt::Program(reader.statements()?);
+        reader.end()?;
+        Ok(program)
+    }
+}
+
+/// Methods for reading expressions.
+impl AstReader {
+    fn expr(&mut self) -> Result<Expr, RloxError> {
+        match self.advance()? {
+            Atom::Quoted(raw) => match unescape(&raw) {
+                Some(value) => Ok(Expr::Literal {
+                    value: LiteralType::String(value),
+                }),
+                None => Err(self.error("Invalid escape string sequence.")),
+            },
+            Atom::Symbol(symbol) => self.symbol(symbol),
+            Atom::LeftParen => {
+                let expr = self.compound()?;
+                self.expect(Atom::RightParen, "Expect ')' after expression.")?;
+                Ok(expr)
+            }
+            _ => Err(self.error("Expect expression.")),
+        }
+    }
+
+    /// Read a literal or variable.
+    fn symbol(&mut self, symbol: String) -> Result<Expr, RloxError> {
+        let value = match symbol.as_str() {
+            "true" => LiteralType::Bool(true),
+            "false" => LiteralType::Bool(false),
+            "nil" => LiteralType::Nil,
+            _ if is_number(&symbol) && symbol.ends_with('n') => {
+                match symbol[..symbol.len() - 1].parse() {
+                    Ok(number) => LiteralType::BigInt(number),
+                    Err(_) => return Err(self.error("Invalid number.")),
+                }
+            }
+            _ if is_number(&symbol) && symbol.ends_with('d') => {
+                match symbol[..symbol.len() - 1].parse() {
+                    Ok(number) => LiteralType::Decimal(number),
+                    Err(_) => return Err(self.error("Invalid number.")),
+                }
+            }
+            _ if is_number(&symbol) && symbol.contains('.') => match symbol.parse::<f64>() {
+                Ok(number) => LiteralType::Number(number),
+                Err(_) => return Err(self.error("Invalid number.")),
+            },
+            _ if is_number(&symbol) => match symbol.parse::<i64>() {
+                Ok(number) => LiteralType::Int(number),
+                Err(_) => return Err(self.error("Invalid number.")),
+            },
+            _ if is_identifier(&symbol) => {
+                return Ok(Expr::Variable {
+                    name: token(TokenType::Identifier, &symbol),
+                });
+            }
+            _ => return Err(self.error("Expect literal or variable.")),
+        };
+
+        Ok(Expr::Literal { value })
+    }
+
+    /// Read a parenthesized expression, after the '('.
+    fn compound(&mut self) -> Result<Expr, RloxError> {
+        let Atom::Symbol(head) = self.advance()? else {
+            return Err(self.error("Expect operator after '('."));
+        };
+        match head.as_str() {
+            "group" => Ok(Expr::Grouping {
+                expression: Box::new(self.expr()?),
+            }),
+            "call" => {
+                let callee = Box::new(self.expr()?);
+                let mut arguments = vec![];
+                while !self.check(&Atom::RightParen) {
+                    arguments.push(self.expr()?);
+                }
+                Ok(Expr::Call {
+                    callee,
+                    paren: token(TokenType::RightParen, ")"),
+                    arguments,
+                })
+            }
+            "=" => {
+                let name = self.identifier()?;
+                Ok(Expr::Assignment {
+                    name,
+                    value: Box::new(self.expr()?),
+                })
+            }
+            "list" => {
+                let mut elements = vec![];
+                while !self.check(&Atom::RightParen) {
+                    elements.push(self.expr()?);
+                }
+                Ok(Expr::List {
+                    bracket: token(TokenType::LeftBracket, "["),
+                    elements,
+                })
+            }
+            "map" => {
+                let mut entries = vec![];
+                while !self.check(&Atom::RightParen) {
+                    entries.push((self.expr()?, self.expr()?));
+                }
+                Ok(Expr::Map {
+                    brace: token(TokenType::LeftBrace, "{"),
+                    entries,
+                })
+            }
+            "index" => Ok(Expr::Index {
+                object: Box::new(self.expr()?),
+                bracket: token(TokenType::LeftBracket, "["),
+                index: Box::new(self.expr()?),
+            }),
+            "index=" => Ok(Expr::IndexSet {
+                object: Box::new(self.expr()?),
+                bracket: token(TokenType::LeftBracket, "["),
+                index: Box::new(self.expr()?),
+                value: Box::new(self.expr()?),
+            }),
+            "fun" => {
+                let params = self.parameters()?;
+                self.expect(Atom::LeftBracket, "Expect '[' before function body.")?;
+                Ok(Expr::Lambda {
+                    keyword: token(TokenType::Fun, "fun"),
+                    params,
+                    body: Rc::new(Stmt::Block(self.statements()?, 0, 0)),
+                })
+            }
+            "=>" => {
+                let params = self.parameters()?;
+                let keyword = token(TokenType::Arrow, "=>");
+                Ok(Expr::Lambda {
+                    body: Rc::new(Stmt::Return(keyword.clone(), Some(self.expr()?))),
+                    keyword,
+                    params,
+                })
+            }
+            "+=" | "-=" | "*=" | "/=" => {
+                let target = self.target()?;
+                let token_type = match head.as_str() {
+                    "+=" => TokenType::PlusEqual,
+                    "-=" => TokenType::MinusEqual,
+                    "*=" => TokenType::StarEqual,
+                    _ => TokenType::SlashEqual,
+                };
+                Ok(Expr::CompoundAssignment {
+                    target: Box::new(target),
+                    operator: token(token_type, &head),
+                    value: Box::new(self.expr()?),
+                })
+            }
+            "++" | "--" | "post++" | "post--" => {
+                let prefix = !head.starts_with("post");
+                let lexeme = head.trim_start_matches("post");
+                let token_type = match lexeme {
+                    "++" => TokenType::PlusPlus,
+                    _ => TokenType::MinusMinus,
+                };
+                Ok(Expr::Update {
+                    operator: token(token_type, lexeme),
+                    target: Box::new(self.target()?),
+                    prefix,
+                })
+            }
+            "?" => Ok(Expr::Ternary {
+                condition: Box::new(self.expr()?),
+                truepart: Box::new(self.expr()?),
+                falsepart: Box::new(self.expr()?),
+            }),
+            _ => {
+                let Some(token_type) = operator_type(&head) else {
+                    return Err(self.error(&format!("Unknown operator: {head}.")));
+                };
+                let operator = token(token_type, &head);
+                let left = self.expr()?;
+                if self.check(&Atom::RightParen)
+                    && matches!(
+                        token_type,
+                        TokenType::Minus | TokenType::Bang | TokenType::Tilde
+                    )
+                {
+                    return Ok(Expr::Unary {
+                        operator,
+                        right: Box::new(left),
+                    });
+                }
+                Ok(Expr::Binary {
+                    left: Box::new(left),
+                    operator,
+                    right: Box::new(self.expr()?),
+                })
+            }
+        }
+    }
+
+    /// Read parenthesized parameter names such as `(a b)`.
+    fn parameters(&mut self) -> Result<Vec<Token>, RloxError> {
+        self.expect(Atom::LeftParen, "Expect '(' before parameters.")?;
+        let mut params = vec![];
+        while !self.check(&Atom::RightParen) {
+            params.push(self.identifier()?);
+        }
+        self.advance()?;
+        Ok(params)
+    }
+
+    /// Read a variable or index to assign to.
+    fn target(&mut self) -> Result<Expr, RloxError> {
+        match self.expr()? {
+            target @ (Expr::Variable { .. } | Expr::Index { .. }) => Ok(target),
+            _ => Err(self.error("Expect variable or index to assign to.")),
+        }
+    }
+
+    fn identifier(&mut self) -> Result<Token, RloxError> {
+        match self.advance()? {
+            Atom::Symbol(symbol) if is_identifier(&symbol) => {
+                Ok(token(TokenType::Identifier, &symbol))
+            }
+            _ => Err(self.error("Expect identifier.")),
+        }
+    }
+}
+
+/// Methods for reading statements.
+impl AstReader {
+    /// Read `;` separated statements until `]`.
+    fn statements(&mut self) -> Result<Vec<Stmt>, RloxError> {
+        let mut statements = vec![];
+        if self.check(&Atom::RightBracket) {
+            self.advance()?;
+            return Ok(statements);
+        }
+        loop {
+            statements.push(self.statement()?);
+            match self.advance()? {
+                Atom::Semicolon => {}
+                Atom::RightBracket => return Ok(statements),
+                _ => return Err(self.error("Expect ';' or ']' after statement.")),
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<Stmt, RloxError> {
+        if self.check(&Atom::LeftBracket) {
+            self.advance()?;
+            return Ok(Stmt::Block(self.statements()?, 0, 0));
+        }
+        let head = match self.atoms.get(self.current + 1) {
+            Some((Atom::Symbol(head), _)) if self.check(&Atom::LeftParen) => head.clone(),
+            _ => return Ok(Stmt::Expression(self.expr()?, 0)),
+        };
+
+        let stmt = match head.as_str() {
+            "print" => {
+                self.current += 2;
+                Stmt::Print(self.expr()?, 0)
+            }
+            "var" => {
+                self.current += 2;
+                let name = self.identifier()?;
+                let mut initializer = None;
+                if self.check(&Atom::Symbol("=".to_owned())) {
+                    self.advance()?;
+                    initializer = Some(self.expr()?);
+                }
+                Stmt::Var(name, initializer)
+            }
+            "while" => {
+                self.current += 2;
+                let condition = self.expr()?;
+                let body = self.statement()?;
+                Stmt::While(condition, Box::new(body), 0)
+            }
+            "for" => {
+                self.current += 2;
+                let name = self.identifier()?;
+                let iterable = self.expr()?;
+                let body = self.statement()?;
+                Stmt::ForIn(name, iterable, Box::new(body), 0)
+            }
+            "if" => {
+                self.current += 2;
+                let condition = self.expr()?;
+                let then_branch = Box::new(self.statement()?);
+                let mut else_branch = None;
+                if !self.check(&Atom::RightParen) {
+                    else_branch = Some(Box::new(self.statement()?));
+                }
+                Stmt::If(condition, then_branch, else_branch, 0)
+            }
+            "return" => {
+                self.current += 2;
+                let mut value = None;
+                if !self.check(&Atom::RightParen) {
+                    value = Some(self.expr()?);
+                }
+                Stmt::Return(token(TokenType::Return, "return"), value)
+            }
+            "break" => {
+                self.current += 2;
+                Stmt::Break(token(TokenType::Break, "break"))
+            }
+            "continue" => {
+                self.current += 2;
+                Stmt::Continue(token(TokenType::Continue, "continue"))
+            }
+            "test" => {
+                self.current += 2;
+                let Atom::Quoted(raw) = self.advance()? else {
+                    return Err(self.error("Expect test name."));
+                };
+                let mut name = token(TokenType::String, &format!("\"{raw}\""));
+                name.literal = LiteralType::String(raw);
+                self.expect(Atom::LeftBracket, "Expect '[' before test body.")?;
+                let body = Stmt::Block(self.statements()?, 0, 0);
+                Stmt::Test(name, Box::new(body))
+            }
+            _ => return Ok(Stmt::Expression(self.expr()?, 0)),
+        };
+        self.expect(Atom::RightParen, "Expect ')' after statement.")?;
+
+        Ok(stmt)
+    }
+}
+
+/// Helper methods for reading.
+impl AstReader {
+    fn check(&self, atom: &Atom) -> bool {
+        self.atoms.get(self.current).is_some_and(|(a, _)| a == atom)
+    }
+
+    fn advance(&mut self) -> Result<Atom, RloxError> {
+        match self.atoms.get(self.current) {
+            Some((atom, _)) => {
+                self.current += 1;
+                Ok(atom.clone())
+            }
+            None => Err(self.error("Unexpected end of input.")),
+        }
+    }
+
+    fn expect(&mut self, atom: Atom, message: &str) -> Result<(), RloxError> {
+        if self.check(&atom) {
+            self.current += 1;
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    /// Make sure all input has been read.
+    fn end(&self) -> Result<(), RloxError> {
+        if self.current < self.atoms.len() {
+            Err(self.error("Unexpected trailing input."))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn error(&self, message: &str) -> RloxError {
+        let (near, line) = match self.atoms.get(self.current.saturating_sub(1)) {
+            Some((atom, line)) => (format!("{:?}", atom), *line),
+            None => (String::new(), 1),
+        };
+        RloxError::SyntaxError(line, near, message.to_owned())
+    }
+}
+
+/// Split S-expression source into atoms with their line.
+fn tokenize(source: &str) -> Result<Vec<(Atom, usize)>, RloxError> {
+    let mut atoms = vec![];
+    let mut line = 1;
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        let atom = match c {
+            '(' => Atom::LeftParen,
+            ')' => Atom::RightParen,
+            '[' => Atom::LeftBracket,
+            ']' => Atom::RightBracket,
+            ';' => Atom::Semicolon,
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            _ if c.is_whitespace() => continue,
+            '"' => {
+                let mut raw = String::new();
+                let mut escaped = false;
+                loop {
+                    match chars.next() {
+                        Some('"') if !escaped => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            escaped = c == '\\' && !escaped;
+                            raw.push(c);
+                        }
+                        None => {
+                            return Err(RloxError::LexicalError(
+                                line,
+                                raw,
+                                "unterminated string".to_owned(),
+                            ));
+                        }
+                    }
+                }
+                Atom::Quoted(raw)
+            }
+            _ => {
+                let mut symbol = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()[];\"".contains(c) {
+                        break;
+                    }
+                    symbol.push(c);
+                    chars.next();
+                }
+                Atom::Symbol(symbol)
+            }
+        };
+        atoms.push((atom, line));
+    }
+
+    Ok(atoms)
+}
+
+fn token(token_type: TokenType, lexeme: &str) -> Token {
+    Token::new(token_type, lexeme.to_owned(), LiteralType::Nil, 0)
+}
+
+fn operator_type(symbol: &str) -> Option<TokenType> {
+    Some(match symbol {
+        "+" => TokenType::Plus,
+        "-" => TokenType::Minus,
+        "*" => TokenType::Star,
+        "/" => TokenType::Slash,
+        "%" => TokenType::Percent,
+        "~/" => TokenType::TildeSlash,
+        "**" => TokenType::StarStar,
+        "&" => TokenType::Ampersand,
+        "|" => TokenType::Pipe,
+        "^" => TokenType::Caret,
+        "~" => TokenType::Tilde,
+        "<<" => TokenType::LessLess,
+        ">>" => TokenType::GreaterGreater,
+        "!" => TokenType::Bang,
+        "==" => TokenType::EqualEqual,
+        "!=" => TokenType::BangEqual,
+        "<" => TokenType::Less,
+        "<=" => TokenType::LessEqual,
+        ">" => TokenType::Greater,
+        ">=" => TokenType::GreaterEqual,
+        ".." => TokenType::DotDot,
+        _ => return None,
+    })
+}
+
+fn is_number(symbol: &str) -> bool {
+    let digits = symbol.strip_prefix('-').unwrap_or(symbol);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn is_identifier(symbol: &str) -> bool {
+    symbol.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
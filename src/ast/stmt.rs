@@ -1,13 +1,31 @@
 use crate::ast::expr::Expr;
 use crate::token::Token;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum Stmt {
-    Block(Vec<Stmt>),
+    /// Statements in block, line of `{` and line of `}`.
+    Block(Vec<Stmt>, usize, usize),
     Program(Vec<Stmt>),
     Var(Token, Option<Expr>),
-    Expression(Expr),
-    Print(Expr),
+    /// Expression and its line.
+    Expression(Expr, usize),
+    /// Printed expression and line of `print`.
+    Print(Expr, usize),
+    /// A named test block, only executed by the test runner.
+    Test(Token, Box<Stmt>),
+    /// Condition, body and line of `while`.
+    While(Expr, Box<Stmt>, usize),
+    /// Loop variable, iterated value, body and line of `for`.
+    ForIn(Token, Expr, Box<Stmt>, usize),
+    /// Condition, then branch, optional else branch and line of `if`.
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>, usize),
+    /// `break` keyword, leaving the innermost loop.
+    Break(Token),
+    /// `continue` keyword, skipping to the next iteration of the innermost loop.
+    Continue(Token),
+    /// `return` keyword and the returned value, if any.
+    Return(Token, Option<Expr>),
 }
 
 pub trait Visitor<T> {
@@ -16,6 +34,18 @@ pub trait Visitor<T> {
     fn visit_var_stmt(&mut self, name: &Token, initializer: &Option<Expr>) -> T;
     fn visit_expression_stmt(&mut self, expression: &Expr) -> T;
     fn visit_print_stmt(&mut self, expression: &Expr) -> T;
+    fn visit_test_stmt(&mut self, name: &Token, body: &Stmt) -> T;
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> T;
+    fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) -> T;
+    fn visit_if_stmt(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: Option<&Stmt>,
+    ) -> T;
+    fn visit_break_stmt(&mut self, keyword: &Token) -> T;
+    fn visit_continue_stmt(&mut self, keyword: &Token) -> T;
+    fn visit_return_stmt(&mut self, keyword: &Token, value: &Option<Expr>) -> T;
 }
 
 impl Stmt {
@@ -24,11 +54,71 @@ impl Stmt {
         V: Visitor<T>,
     {
         match self {
-            Stmt::Block(statements) => visitor.visit_block_stmt(statements),
-            Stmt::Expression(expression) => visitor.visit_expression_stmt(expression),
-            Stmt::Print(expression) => visitor.visit_print_stmt(expression),
+            Stmt::Block(statements, ..) => visitor.visit_block_stmt(statements),
+            Stmt::Expression(expression, _) => visitor.visit_expression_stmt(expression),
+            Stmt::Print(expression, _) => visitor.visit_print_stmt(expression),
             Stmt::Program(declarations) => visitor.visit_program_stmt(declarations),
             Stmt::Var(name, initializer) => visitor.visit_var_stmt(name, initializer),
+            Stmt::Test(name, body) => visitor.visit_test_stmt(name, body),
+            Stmt::While(condition, body, _) => visitor.visit_while_stmt(condition, body),
+            Stmt::ForIn(name, iterable, body, _) => visitor.visit_for_in_stmt(name, iterable, body),
+            Stmt::If(condition, then_branch, else_branch, _) => {
+                visitor.visit_if_stmt(condition, then_branch, else_branch.as_deref())
+            }
+            Stmt::Break(keyword) => visitor.visit_break_stmt(keyword),
+            Stmt::Continue(keyword) => visitor.visit_continue_stmt(keyword),
+            Stmt::Return(keyword, value) => visitor.visit_return_stmt(keyword, value),
+        }
+    }
+
+    /// Name of the statement kind, e.g. `Print`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Stmt::Block(..) => "Block",
+            Stmt::Program(_) => "Program",
+            Stmt::Var(..) => "Var",
+            Stmt::Expression(..) => "Expression",
+            Stmt::Print(..) => "Print",
+            Stmt::Test(..) => "Test",
+            Stmt::While(..) => "While",
+            Stmt::ForIn(..) => "ForIn",
+            Stmt::If(..) => "If",
+            Stmt::Break(_) => "Break",
+            Stmt::Continue(_) => "Continue",
+            Stmt::Return(..) => "Return",
+        }
+    }
+
+    /// Line where the statement starts.
+    pub fn line(&self) -> usize {
+        match self {
+            Stmt::Block(_, line, _)
+            | Stmt::Expression(_, line)
+            | Stmt::Print(_, line)
+            | Stmt::While(_, _, line)
+            | Stmt::ForIn(_, _, _, line)
+            | Stmt::If(_, _, _, line) => *line,
+            Stmt::Program(_) => 1,
+            Stmt::Var(name, _)
+            | Stmt::Test(name, _)
+            | Stmt::Break(name)
+            | Stmt::Continue(name)
+            | Stmt::Return(name, _) => name.line,
+        }
+    }
+
+    /// Line where the statement ends, only known to differ from start line for blocks
+    /// and statements with a body.
+    pub fn end_line(&self) -> usize {
+        match self {
+            Stmt::Block(_, _, end) => *end,
+            Stmt::Test(_, body) | Stmt::While(_, body, _) | Stmt::ForIn(_, _, body, _) => {
+                body.end_line()
+            }
+            Stmt::If(_, then_branch, else_branch, _) => {
+                else_branch.as_ref().unwrap_or(then_branch).end_line()
+            }
+            _ => self.line(),
         }
     }
 }
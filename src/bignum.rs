@@ -0,0 +1,624 @@
+//! Arbitrary-precision integers and exact decimals, for values that must not round.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// Base of the limbs of a magnitude, a power of ten so that printing and
+/// scaling decimals stay cheap.
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+/// Most digits a power may produce, keeping `2n ** 4000000000` from eating all memory.
+const MAX_POWER_DIGITS: f64 = 100_000.0;
+/// Fractional digits of a decimal quotient that doesn't end earlier.
+const DIVISION_SCALE: u32 = 20;
+
+/// Error of parsing a string that isn't a number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError;
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid number")
+    }
+}
+
+/// Integer of any size.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct BigInt {
+    /// Never set for zero.
+    negative: bool,
+    /// Magnitude as little-endian limbs below `BASE`, without leading zero limbs.
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, limbs: Vec<u32>) -> Self {
+        let limbs = trim(limbs);
+        Self {
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.limbs.clone())
+    }
+
+    /// `10 ** exponent`.
+    pub fn pow10(exponent: u32) -> Self {
+        let exponent = exponent as usize;
+        let mut limbs = vec![0; exponent / BASE_DIGITS];
+        limbs.push(10u32.pow((exponent % BASE_DIGITS) as u32));
+        Self::from_parts(false, limbs)
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.limbs.len() > 3 {
+            return None;
+        }
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0i128, |acc, &limb| acc * BASE as i128 + limb as i128);
+        let value = if self.negative { -magnitude } else { magnitude };
+        i64::try_from(value).ok()
+    }
+
+    /// Nearest float, infinite if out of range.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// `self ** exponent`, `None` if the result would be unreasonably large.
+    pub fn pow(&self, exponent: u32) -> Option<Self> {
+        if self.to_f64().abs().log10() * exponent as f64 > MAX_POWER_DIGITS {
+            return None;
+        }
+        let mut result = BigInt::from(1);
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        Some(result)
+    }
+
+    /// Quotient rounded down and remainder taking the sign of the divisor, so that
+    /// `self == quotient * other + remainder`. `None` when dividing by zero.
+    pub fn div_rem_floor(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = div_rem_magnitude(&self.limbs, &other.limbs);
+        let mut quotient = Self::from_parts(self.negative != other.negative, quotient);
+        let mut remainder = Self::from_parts(self.negative, remainder);
+        if !remainder.is_zero() && remainder.negative != other.negative {
+            quotient = &quotient - &BigInt::from(1);
+            remainder = &remainder + other;
+        }
+        Some((quotient, remainder))
+    }
+
+    /// Divide by ten while it leaves no remainder, returning the number of divisions.
+    fn strip_zeros(&mut self, max: u32) -> u32 {
+        if self.is_zero() {
+            return max;
+        }
+        let mut stripped = 0;
+        while stripped < max && self.limbs.first().is_some_and(|limb| limb % 10 == 0) {
+            let mut carry = 0u64;
+            for limb in self.limbs.iter_mut().rev() {
+                let current = carry * BASE + *limb as u64;
+                *limb = (current / 10) as u32;
+                carry = current % 10;
+            }
+            self.limbs = trim(std::mem::take(&mut self.limbs));
+            stripped += 1;
+        }
+        stripped
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> Self {
+        let mut magnitude = n.unsigned_abs();
+        let mut limbs = vec![];
+        while magnitude > 0 {
+            limbs.push((magnitude % BASE) as u32);
+            magnitude /= BASE;
+        }
+        Self::from_parts(n < 0, limbs)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitude(&self.limbs, &other.limbs),
+            (true, true) => compare_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.limbs.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitude(&self.limbs, &other.limbs));
+        }
+        match compare_magnitude(&self.limbs, &other.limbs) {
+            Ordering::Less => {
+                BigInt::from_parts(other.negative, sub_magnitude(&other.limbs, &self.limbs))
+            }
+            _ => BigInt::from_parts(self.negative, sub_magnitude(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            mul_magnitude(&self.limbs, &other.limbs),
+        )
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((top, rest)) = self.limbs.split_last() else {
+            return write!(f, "0");
+        };
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{top}")?;
+        for limb in rest.iter().rev() {
+            write!(f, "{limb:09}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseError;
+
+    /// Parse decimal digits with an optional sign.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError);
+        }
+        let limbs = digits
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u32, |acc, &digit| acc * 10 + (digit - b'0') as u32)
+            })
+            .collect();
+        Ok(Self::from_parts(negative, limbs))
+    }
+}
+
+impl From<BigInt> for String {
+    fn from(n: BigInt) -> Self {
+        n.to_string()
+    }
+}
+
+impl TryFrom<String> for BigInt {
+    type Error = ParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Exact decimal number, `mantissa / 10 ** scale`.
+///
+/// The scale is kept as written, so `1.10d` prints as `1.10`, but it doesn't
+/// take part in comparisons: `1.10d == 1.1d`.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct Decimal {
+    mantissa: BigInt,
+    scale: u32,
+}
+
+impl Decimal {
+    pub fn new(mantissa: BigInt, scale: u32) -> Self {
+        Self { mantissa, scale }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(self.mantissa.abs(), self.scale)
+    }
+
+    /// Exact value of a float as written by `Display`, so `0.1` is `0.1d`.
+    pub fn from_f64(n: f64) -> Option<Self> {
+        if n.is_finite() {
+            n.to_string().parse().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Exact binary value of a float, so `0.5` is `0.5d` but `0.1` has 55 digits.
+    pub fn from_f64_exact(n: f64) -> Option<Self> {
+        if !n.is_finite() {
+            return None;
+        }
+        let bits = n.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = (bits & ((1 << 52) - 1)) as i64;
+        // |n| == mantissa * 2 ** exponent, subnormals having no implicit bit.
+        let (mantissa, exponent) = match exponent {
+            0 => (fraction, -1074),
+            _ => (fraction | 1 << 52, exponent - 1075),
+        };
+        let mantissa = BigInt::from(if n < 0.0 { -mantissa } else { mantissa });
+        if exponent >= 0 {
+            let power = BigInt::from(2).pow(exponent as u32)?;
+            Some(Self::from(&mantissa * &power))
+        } else {
+            // 2 ** -scale == 5 ** scale / 10 ** scale
+            let scale = exponent.unsigned_abs();
+            Some(Self::new(&mantissa * &BigInt::from(5).pow(scale)?, scale))
+        }
+    }
+
+    /// Nearest float.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.normalized().scale == 0
+    }
+
+    /// Integer part, rounding toward zero.
+    pub fn trunc(&self) -> BigInt {
+        let (quotient, _) =
+            div_rem_magnitude(&self.mantissa.limbs, &BigInt::pow10(self.scale).limbs);
+        BigInt::from_parts(self.mantissa.negative, quotient)
+    }
+
+    /// Largest integer less than or equal to the decimal.
+    pub fn floor(&self) -> BigInt {
+        let (quotient, _) = self
+            .mantissa
+            .div_rem_floor(&BigInt::pow10(self.scale))
+            .expect("a power of ten isn't zero");
+        quotient
+    }
+
+    /// Smallest integer greater than or equal to the decimal.
+    pub fn ceil(&self) -> BigInt {
+        -&(-self).floor()
+    }
+
+    /// Quotient, exact if it ends within `DIVISION_SCALE` digits or the scale of
+    /// an operand, else rounded half to even. `None` when dividing by zero.
+    pub fn div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let min_scale = self.scale.max(other.scale);
+        let scale = DIVISION_SCALE.max(min_scale);
+        // self / other == (self.mantissa * 10 ** shift / other.mantissa) / 10 ** scale
+        let shift = scale + other.scale - self.scale;
+        let dividend = &self.mantissa * &BigInt::pow10(shift);
+        let (quotient, remainder) = div_rem_magnitude(&dividend.limbs, &other.mantissa.limbs);
+        let mut quotient = BigInt::from_parts(false, quotient);
+        let twice = add_magnitude(&remainder, &remainder);
+        let round_up = match compare_magnitude(&twice, &other.mantissa.limbs) {
+            Ordering::Greater => true,
+            Ordering::Equal => quotient.limbs.first().is_some_and(|limb| limb % 2 == 1),
+            Ordering::Less => false,
+        };
+        if round_up {
+            quotient = &quotient + &BigInt::from(1);
+        }
+        quotient.negative =
+            self.mantissa.negative != other.mantissa.negative && !quotient.is_zero();
+        let stripped = quotient.strip_zeros(scale - min_scale);
+        Some(Self::new(quotient, scale - stripped))
+    }
+
+    /// Quotient rounded down and remainder taking the sign of the divisor.
+    /// `None` when dividing by zero.
+    pub fn div_rem_floor(&self, other: &Self) -> Option<(Self, Self)> {
+        let scale = self.scale.max(other.scale);
+        let (quotient, remainder) = self.rescaled(scale).div_rem_floor(&other.rescaled(scale))?;
+        Some((Self::new(quotient, 0), Self::new(remainder, scale)))
+    }
+
+    /// `self ** exponent`, `None` if the result would be unreasonably large.
+    pub fn pow(&self, exponent: u32) -> Option<Self> {
+        let scale = self.scale.checked_mul(exponent)?;
+        Some(Self::new(self.mantissa.pow(exponent)?, scale))
+    }
+
+    /// Mantissa for a scale at least the current one.
+    fn rescaled(&self, scale: u32) -> BigInt {
+        &self.mantissa * &BigInt::pow10(scale - self.scale)
+    }
+
+    /// Same number without trailing zeros in its fractional part.
+    fn normalized(&self) -> Self {
+        let mut mantissa = self.mantissa.clone();
+        let stripped = mantissa.strip_zeros(self.scale);
+        Self::new(mantissa, self.scale - stripped)
+    }
+}
+
+impl From<BigInt> for Decimal {
+    fn from(n: BigInt) -> Self {
+        Self::new(n, 0)
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(n: i64) -> Self {
+        Self::new(BigInt::from(n), 0)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.mantissa.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        self.rescaled(scale).cmp(&other.rescaled(scale))
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::new(-&self.mantissa, self.scale)
+    }
+}
+
+impl Add for &Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: &Decimal) -> Decimal {
+        let scale = self.scale.max(other.scale);
+        Decimal::new(&self.rescaled(scale) + &other.rescaled(scale), scale)
+    }
+}
+
+impl Sub for &Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: &Decimal) -> Decimal {
+        self + &-other
+    }
+}
+
+impl Mul for &Decimal {
+    type Output = Decimal;
+
+    fn mul(self, other: &Decimal) -> Decimal {
+        Decimal::new(&self.mantissa * &other.mantissa, self.scale + other.scale)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.abs().to_string();
+        let scale = self.scale as usize;
+        if self.mantissa.negative {
+            write!(f, "-")?;
+        }
+        if scale == 0 {
+            return write!(f, "{digits}");
+        }
+        // Pad so that there is a digit before the point.
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{whole}.{fraction}")
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseError;
+
+    /// Parse digits with an optional sign and fractional part, such as `-12.50`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if s.contains('.') && (fraction.is_empty() || fraction.starts_with(['-', '+'])) {
+            return Err(ParseError);
+        }
+        let mantissa = format!("{whole}{fraction}").parse()?;
+        Ok(Self::new(mantissa, fraction.len() as u32))
+    }
+}
+
+impl From<Decimal> for String {
+    fn from(n: Decimal) -> Self {
+        n.to_string()
+    }
+}
+
+impl TryFrom<String> for Decimal {
+    type Error = ParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+fn trim(mut limbs: Vec<u32>) -> Vec<u32> {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+fn compare_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let current = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push((current % BASE) as u32);
+        carry = current / BASE;
+    }
+    sum.push(carry as u32);
+    trim(sum)
+}
+
+/// `a - b`, where `a` is at least `b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut current = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = (current < 0) as i64;
+        if current < 0 {
+            current += BASE as i64;
+        }
+        difference.push(current as u32);
+    }
+    trim(difference)
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut product = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        // Each step stays below BASE ** 2, and the carry below BASE.
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let current = product[i + j] + x as u64 * y as u64 + carry;
+            product[i + j] = current % BASE;
+            carry = current / BASE;
+        }
+        product[i + b.len()] = carry;
+    }
+    trim(product.into_iter().map(|limb| limb as u32).collect())
+}
+
+/// Quotient and remainder of magnitudes, by long division finding each limb of
+/// the quotient with a binary search. `b` must not be zero.
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare_magnitude(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+    let mut quotient = vec![0; a.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for i in (0..a.len()).rev() {
+        remainder.insert(0, a[i]);
+        remainder = trim(remainder);
+        let (mut low, mut high) = (0, BASE as u32 - 1);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if compare_magnitude(&mul_magnitude(b, &[mid]), &remainder) == Ordering::Greater {
+                high = mid - 1;
+            } else {
+                low = mid;
+            }
+        }
+        if low > 0 {
+            remainder = sub_magnitude(&remainder, &mul_magnitude(b, &[low]));
+        }
+        quotient[i] = low;
+    }
+    (trim(quotient), remainder)
+}
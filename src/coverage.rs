@@ -0,0 +1,388 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+    rc::Rc,
+};
+
+use crate::{
+    ast::{
+        expr::{self, Expr},
+        stmt::{self, Stmt},
+    },
+    environment::Environment,
+    error::RloxError,
+    interpreter::Hook,
+    token::{LiteralType, Token},
+};
+
+/// Both arms of a ternary expression or `if` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchCoverage {
+    pub line: usize,
+    /// Times the true and the false arm were taken.
+    pub taken: [u64; 2],
+}
+
+/// Coverage of a single source file.
+#[derive(Debug, Default, PartialEq)]
+pub struct FileCoverage {
+    /// Hits of each line where a statement starts.
+    pub lines: BTreeMap<usize, u64>,
+    /// Ternary expressions and `if` statements in source order.
+    pub branches: Vec<BranchCoverage>,
+}
+
+impl FileCoverage {
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|&&hits| hits > 0).count()
+    }
+
+    /// Number of arms, two per branch.
+    pub fn branches_found(&self) -> usize {
+        self.branches.len() * 2
+    }
+
+    pub fn branches_hit(&self) -> usize {
+        self.branches
+            .iter()
+            .flat_map(|branch| branch.taken)
+            .filter(|&taken| taken > 0)
+            .count()
+    }
+}
+
+/// Coverage of all files of a run, keyed by path.
+#[derive(Debug, Default)]
+pub struct Coverage {
+    pub files: BTreeMap<String, Rc<RefCell<FileCoverage>>>,
+}
+
+impl Coverage {
+    /// Return the coverage of a file, shared with the collectors recording it.
+    pub fn file(&mut self, path: &str) -> Rc<RefCell<FileCoverage>> {
+        Rc::clone(self.files.entry(path.to_owned()).or_default())
+    }
+
+    /// Percentage of lines hit over all files, 100 when there's nothing to cover.
+    pub fn line_percent(&self) -> f64 {
+        let (hit, found) = self.files.values().fold((0, 0), |(hit, found), file| {
+            let file = file.borrow();
+            (hit + file.lines_hit(), found + file.lines.len())
+        });
+        percent(hit, found)
+    }
+
+    /// Render coverage in the lcov tracefile format.
+    pub fn lcov(&self) -> String {
+        let mut s = String::new();
+        for (path, file) in &self.files {
+            let file = file.borrow();
+            writeln!(s, "TN:").unwrap();
+            writeln!(s, "SF:{path}").unwrap();
+            for (line, hits) in &file.lines {
+                writeln!(s, "DA:{line},{hits}").unwrap();
+            }
+            for (block, branch) in file.branches.iter().enumerate() {
+                // Arms of a branch never reached are reported as `-`.
+                let reached = branch.taken.iter().any(|&taken| taken > 0);
+                for (arm, taken) in branch.taken.iter().enumerate() {
+                    let taken = if reached {
+                        taken.to_string()
+                    } else {
+                        "-".to_owned()
+                    };
+                    writeln!(s, "BRDA:{},{},{},{}", branch.line, block, arm, taken).unwrap();
+                }
+            }
+            writeln!(s, "BRF:{}", file.branches_found()).unwrap();
+            writeln!(s, "BRH:{}", file.branches_hit()).unwrap();
+            writeln!(s, "LF:{}", file.lines.len()).unwrap();
+            writeln!(s, "LH:{}", file.lines_hit()).unwrap();
+            writeln!(s, "end_of_record").unwrap();
+        }
+
+        s
+    }
+
+    /// Render a per file summary of line and branch coverage.
+    pub fn summary(&self) -> String {
+        let mut s = String::new();
+        writeln!(s, "Coverage:").unwrap();
+        let (mut lines_hit, mut lines_found) = (0, 0);
+        let (mut branches_hit, mut branches_found) = (0, 0);
+        for (path, file) in &self.files {
+            let file = file.borrow();
+            summary_row(
+                &mut s,
+                path,
+                (file.lines_hit(), file.lines.len()),
+                (file.branches_hit(), file.branches_found()),
+            );
+            lines_hit += file.lines_hit();
+            lines_found += file.lines.len();
+            branches_hit += file.branches_hit();
+            branches_found += file.branches_found();
+        }
+        summary_row(
+            &mut s,
+            "total",
+            (lines_hit, lines_found),
+            (branches_hit, branches_found),
+        );
+
+        s
+    }
+}
+
+fn summary_row(s: &mut String, name: &str, lines: (usize, usize), branches: (usize, usize)) {
+    writeln!(
+        s,
+        "  {}: lines {}/{} ({:.1}%), branches {}/{} ({:.1}%)",
+        name,
+        lines.0,
+        lines.1,
+        percent(lines.0, lines.1),
+        branches.0,
+        branches.1,
+        percent(branches.0, branches.1)
+    )
+    .unwrap();
+}
+
+fn percent(hit: usize, found: usize) -> f64 {
+    if found == 0 {
+        100.0
+    } else {
+        hit as f64 * 100.0 / found as f64
+    }
+}
+
+/// Hook recording executed statements and taken branch arms of a program.
+#[derive(Clone)]
+pub struct CoverageCollector {
+    file: Rc<RefCell<FileCoverage>>,
+    /// Index of each branch in `FileCoverage::branches`, by address of its condition.
+    branches: HashMap<*const Expr, usize>,
+}
+
+impl CoverageCollector {
+    /// Create a collector for a program, registering its lines and branches
+    /// in `file` unless done by an earlier run of the same program.
+    pub fn new(program: &Stmt, file: Rc<RefCell<FileCoverage>>) -> Self {
+        let mut registrar = Registrar::default();
+        program.accept(&mut registrar);
+
+        let mut coverage = file.borrow_mut();
+        if coverage.lines.is_empty() && coverage.branches.is_empty() {
+            coverage.lines = registrar.lines.into_iter().map(|line| (line, 0)).collect();
+            coverage.branches = registrar
+                .branches
+                .iter()
+                .map(|&(_, line)| BranchCoverage {
+                    line,
+                    taken: [0, 0],
+                })
+                .collect();
+        }
+        drop(coverage);
+
+        Self {
+            file,
+            branches: registrar
+                .branches
+                .into_iter()
+                .enumerate()
+                .map(|(i, (condition, _))| (condition, i))
+                .collect(),
+        }
+    }
+}
+
+impl Hook for CoverageCollector {
+    fn before_stmt(
+        &mut self,
+        stmt: &Stmt,
+        _environment: &Environment,
+        _calls: usize,
+    ) -> Result<(), RloxError> {
+        // Skipping a test block doesn't cover the line, running its body does.
+        if matches!(stmt, Stmt::Test(..)) {
+            return Ok(());
+        }
+        if let Some(hits) = self.file.borrow_mut().lines.get_mut(&stmt.line()) {
+            *hits += 1;
+        }
+        Ok(())
+    }
+
+    fn branch(&mut self, condition: &Expr, taken: bool) -> Result<(), RloxError> {
+        if let Some(&i) = self.branches.get(&(condition as *const Expr)) {
+            let arm = if taken { 0 } else { 1 };
+            self.file.borrow_mut().branches[i].taken[arm] += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Walk a program collecting statement lines and branches.
+#[derive(Default)]
+struct Registrar {
+    /// Line of the statement being walked, for conditions without a line.
+    line: usize,
+    lines: BTreeSet<usize>,
+    branches: Vec<(*const Expr, usize)>,
+}
+
+impl Registrar {
+    fn statement(&mut self, stmt: &Stmt) {
+        self.line = stmt.line();
+        // Test statements are skipped when executed, only their body runs.
+        if !matches!(stmt, Stmt::Test(..)) {
+            self.lines.insert(self.line);
+        }
+        stmt.accept(self);
+    }
+}
+
+impl expr::Visitor<()> for Registrar {
+    fn visit_assignment_expr(&mut self, _name: &Token, value: &Expr) {
+        value.accept(self);
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, _operator: &Token, right: &Expr) {
+        left.accept(self);
+        right.accept(self);
+    }
+
+    fn visit_call(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) {
+        callee.accept(self);
+        for argument in arguments {
+            argument.accept(self);
+        }
+    }
+
+    fn visit_grouping(&mut self, expression: &Expr) {
+        expression.accept(self);
+    }
+
+    fn visit_literal(&mut self, _value: &LiteralType) {}
+
+    fn visit_unary(&mut self, _operator: &Token, right: &Expr) {
+        right.accept(self);
+    }
+
+    fn visit_ternary(&mut self, condition: &Expr, truepart: &Expr, falsepart: &Expr) {
+        let line = condition.line().unwrap_or(self.line);
+        self.branches.push((condition as *const Expr, line));
+        condition.accept(self);
+        truepart.accept(self);
+        falsepart.accept(self);
+    }
+
+    fn visit_variable(&mut self, _name: &Token) {}
+
+    fn visit_list(&mut self, _bracket: &Token, elements: &[Expr]) {
+        for element in elements {
+            element.accept(self);
+        }
+    }
+
+    fn visit_map(&mut self, _brace: &Token, entries: &[(Expr, Expr)]) {
+        for (key, value) in entries {
+            key.accept(self);
+            value.accept(self);
+        }
+    }
+
+    fn visit_index(&mut self, object: &Expr, _bracket: &Token, index: &Expr) {
+        object.accept(self);
+        index.accept(self);
+    }
+
+    fn visit_index_set(&mut self, object: &Expr, _bracket: &Token, index: &Expr, value: &Expr) {
+        object.accept(self);
+        index.accept(self);
+        value.accept(self);
+    }
+
+    fn visit_compound_assignment(&mut self, target: &Expr, _operator: &Token, value: &Expr) {
+        target.accept(self);
+        value.accept(self);
+    }
+
+    fn visit_update(&mut self, _operator: &Token, target: &Expr, _prefix: bool) {
+        target.accept(self);
+    }
+
+    fn visit_lambda(&mut self, _keyword: &Token, _params: &[Token], body: &Rc<Stmt>) {
+        let line = self.line;
+        // Statements of a block body run on their own, the block itself never does.
+        match body.as_ref() {
+            Stmt::Block(statements, ..) => statements.iter().for_each(|stmt| self.statement(stmt)),
+            body => self.statement(body),
+        }
+        self.line = line;
+    }
+}
+
+impl stmt::Visitor<()> for Registrar {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            self.statement(stmt);
+        }
+    }
+
+    fn visit_program_stmt(&mut self, declarations: &[Stmt]) {
+        for stmt in declarations {
+            self.statement(stmt);
+        }
+    }
+
+    fn visit_var_stmt(&mut self, _name: &Token, initializer: &Option<Expr>) {
+        if let Some(expr) = initializer {
+            expr.accept(self);
+        }
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) {
+        expression.accept(self);
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) {
+        expression.accept(self);
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) {
+        condition.accept(self);
+        self.statement(body);
+    }
+
+    fn visit_for_in_stmt(&mut self, _name: &Token, iterable: &Expr, body: &Stmt) {
+        iterable.accept(self);
+        self.statement(body);
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) {
+        self.branches.push((condition as *const Expr, self.line));
+        condition.accept(self);
+        self.statement(then_branch);
+        if let Some(else_branch) = else_branch {
+            self.statement(else_branch);
+        }
+    }
+
+    fn visit_break_stmt(&mut self, _keyword: &Token) {}
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: &Option<Expr>) {
+        if let Some(expr) = value {
+            expr.accept(self);
+        }
+    }
+
+    fn visit_continue_stmt(&mut self, _keyword: &Token) {}
+
+    fn visit_test_stmt(&mut self, _name: &Token, body: &Stmt) {
+        self.statement(body);
+    }
+}
@@ -0,0 +1,13 @@
+//! Debug adapter for lox, speaking DAP over stdio.
+
+pub mod server;
+
+use std::io;
+
+use crate::error::RloxError;
+
+/// Serve DAP requests from stdin until the client disconnects.
+pub fn run_stdio() -> Result<(), RloxError> {
+    server::serve(io::stdin().lock(), io::stdout())?;
+    Ok(())
+}
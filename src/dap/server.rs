@@ -0,0 +1,435 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeSet,
+    fs,
+    io::{self, BufRead, Write},
+    path::Path,
+    rc::Rc,
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    ast::stmt::Stmt,
+    debugger::{Debugger, Depth, StepMode},
+    environment::Environment,
+    error::RloxError,
+    interpreter::{Hook, Interpreter},
+    parser::Parser,
+    rpc::{read_message, write_message},
+    scanner::Scanner,
+    value::LoxValue,
+};
+
+/// Lox runs on a single thread.
+const THREAD_ID: u64 = 1;
+/// Exit code reported when the program ends with an error.
+const EXIT_FAILURE: i64 = 70;
+
+/// A program loaded by `launch`.
+struct Program {
+    path: String,
+    stmt: Stmt,
+}
+
+/// State shared by the request loop, the debugger hook and program output.
+struct Session<R, W> {
+    reader: R,
+    writer: W,
+    seq: u64,
+    debugger: Debugger,
+    /// Lines where a statement starts, once a program is loaded.
+    lines: Option<BTreeSet<usize>>,
+    disconnected: bool,
+}
+
+impl<R: BufRead, W: Write> Session<R, W> {
+    fn read(&mut self) -> io::Result<Option<Value>> {
+        read_message(&mut self.reader)
+    }
+
+    fn send(&mut self, mut message: Value) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.writer, &message)
+    }
+
+    fn respond(&mut self, request: &Value, body: Value) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": true,
+            "command": request["command"],
+            "body": body,
+        }))
+    }
+
+    fn fail(&mut self, request: &Value, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": false,
+            "command": request["command"],
+            "message": message,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    /// Handle requests valid whether or not the program is paused.
+    fn common(&mut self, request: &Value) -> io::Result<()> {
+        match request["command"].as_str().unwrap_or_default() {
+            "threads" => self.respond(
+                request,
+                json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+            ),
+            "setBreakpoints" => {
+                let lines: Vec<usize> = request["arguments"]["breakpoints"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                    .map(|line| line as usize)
+                    .collect();
+                let breakpoints: Vec<Value> = lines
+                    .iter()
+                    .map(|line| {
+                        // Breakpoints set before launch can't be checked yet.
+                        let verified = self.lines.as_ref().is_none_or(|lines| lines.contains(line));
+                        json!({ "verified": verified, "line": line })
+                    })
+                    .collect();
+                self.debugger.set_breakpoints(lines);
+                self.respond(request, json!({ "breakpoints": breakpoints }))
+            }
+            "stackTrace" | "scopes" | "variables" | "continue" | "next" | "stepIn" | "stepOut" => {
+                self.fail(request, "Program is not paused.")
+            }
+            command => self.fail(request, &format!("Unknown command: {command}.")),
+        }
+    }
+}
+
+/// Serve DAP requests until `disconnect` or end of input, then hand back the writer.
+pub fn serve<R, W>(reader: R, writer: W) -> io::Result<W>
+where
+    R: BufRead + 'static,
+    W: Write + 'static,
+{
+    let session = Rc::new(RefCell::new(Session {
+        reader,
+        writer,
+        seq: 0,
+        debugger: Debugger::new(),
+        lines: None,
+        disconnected: false,
+    }));
+    let mut program = None;
+
+    loop {
+        let Some(request) = session.borrow_mut().read()? else {
+            break;
+        };
+        let mut session_ref = session.borrow_mut();
+        match request["command"].as_str().unwrap_or_default() {
+            "initialize" => {
+                session_ref.respond(
+                    &request,
+                    json!({ "supportsConfigurationDoneRequest": true }),
+                )?;
+                session_ref.event("initialized", json!({}))?;
+            }
+            "launch" => match load(&request["arguments"]) {
+                Ok(loaded) => {
+                    let mut lines = BTreeSet::new();
+                    statement_lines(&loaded.stmt, &mut lines);
+                    session_ref.lines = Some(lines);
+                    session_ref.debugger.stop_on_entry =
+                        request["arguments"]["stopOnEntry"].as_bool() == Some(true);
+                    program = Some(loaded);
+                    session_ref.respond(&request, json!({}))?;
+                }
+                Err(message) => session_ref.fail(&request, &message)?,
+            },
+            "configurationDone" => {
+                session_ref.respond(&request, json!({}))?;
+                if let Some(program) = program.take() {
+                    drop(session_ref);
+                    run(&session, program)?;
+                    if session.borrow().disconnected {
+                        break;
+                    }
+                }
+            }
+            "disconnect" => {
+                session_ref.respond(&request, json!({}))?;
+                break;
+            }
+            _ => session_ref.common(&request)?,
+        }
+    }
+
+    match Rc::try_unwrap(session) {
+        Ok(session) => Ok(session.into_inner().writer),
+        Err(_) => unreachable!("interpreter holding the session has been dropped"),
+    }
+}
+
+/// Read and parse the program given in `launch` arguments.
+fn load(arguments: &Value) -> Result<Program, String> {
+    let Some(path) = arguments["program"].as_str() else {
+        return Err("Missing program to launch.".to_owned());
+    };
+    let source = fs::read_to_string(path).map_err(|e| format!("Could not read {path}: {e}."))?;
+
+    let mut scanner = Scanner::new(source);
+    let Ok(tokens) = scanner.scan_tokens() else {
+        return Err(messages(&scanner.errors));
+    };
+    let mut parser = Parser::new(tokens);
+    let program = match parser.parse() {
+        Some(program) if !parser.had_error => program,
+        _ => return Err(messages(&parser.errors)),
+    };
+
+    Ok(Program {
+        path: path.to_owned(),
+        stmt: program,
+    })
+}
+
+fn messages(errors: &[RloxError]) -> String {
+    let messages: Vec<String> = errors.iter().map(RloxError::to_string).collect();
+    messages.join("\n")
+}
+
+/// Collect lines of statements which can be paused at.
+fn statement_lines(stmt: &Stmt, lines: &mut BTreeSet<usize>) {
+    match stmt {
+        Stmt::Program(statements) => {
+            for stmt in statements {
+                statement_lines(stmt, lines);
+            }
+        }
+        Stmt::Block(statements, ..) => {
+            lines.insert(stmt.line());
+            for stmt in statements {
+                statement_lines(stmt, lines);
+            }
+        }
+        Stmt::While(_, body, _) | Stmt::ForIn(_, _, body, _) => {
+            lines.insert(stmt.line());
+            statement_lines(body, lines);
+        }
+        Stmt::If(_, then_branch, else_branch, _) => {
+            lines.insert(stmt.line());
+            statement_lines(then_branch, lines);
+            if let Some(else_branch) = else_branch {
+                statement_lines(else_branch, lines);
+            }
+        }
+        // Test blocks are not run by `rlox dap`.
+        Stmt::Test(..) => {}
+        _ => {
+            lines.insert(stmt.line());
+        }
+    }
+}
+
+/// Run a loaded program, reporting its output and end as events.
+fn run<R, W>(session: &Rc<RefCell<Session<R, W>>>, program: Program) -> io::Result<()>
+where
+    R: BufRead + 'static,
+    W: Write + 'static,
+{
+    let mut interpreter = Interpreter::new();
+    // Stdin carries the protocol, so the program reads an empty input.
+    interpreter.set_input(Box::new(io::empty()));
+    interpreter.set_output(Box::new(OutputEvents {
+        session: Rc::clone(session),
+        buffer: vec![],
+    }));
+    interpreter.set_hook(Box::new(DebugHook {
+        session: Rc::clone(session),
+        path: program.path,
+    }));
+    let result = interpreter.execute(&program.stmt);
+    drop(interpreter);
+
+    let mut session = session.borrow_mut();
+    if session.disconnected {
+        return Ok(());
+    }
+    let exit_code = match result {
+        Ok(()) => 0,
+        Err(RloxError::Exit(status)) => i64::from(status),
+        Err(e) => {
+            session.event(
+                "output",
+                json!({ "category": "stderr", "output": format!("{e}\n") }),
+            )?;
+            EXIT_FAILURE
+        }
+    };
+    session.event("exited", json!({ "exitCode": exit_code }))?;
+    session.event("terminated", json!({}))
+}
+
+/// Pauses the interpreter and answers requests until it's resumed.
+struct DebugHook<R, W> {
+    session: Rc<RefCell<Session<R, W>>>,
+    path: String,
+}
+
+impl<R: BufRead, W: Write> DebugHook<R, W> {
+    fn stack_trace(&self, stmt: &Stmt) -> Value {
+        let name = Path::new(&self.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        json!({
+            "stackFrames": [{
+                "id": 0,
+                "name": "main",
+                "line": stmt.line(),
+                "column": 1,
+                "source": { "name": name, "path": self.path },
+            }],
+            "totalFrames": 1,
+        })
+    }
+}
+
+/// Scopes are numbered from 1 innermost first, the last one being globals.
+fn scopes(scopes: &[Vec<(String, LoxValue)>]) -> Value {
+    let scopes: Vec<Value> = (0..scopes.len())
+        .map(|i| {
+            let name = if i == scopes.len() - 1 {
+                "Globals"
+            } else if i == 0 {
+                "Locals"
+            } else {
+                "Enclosing"
+            };
+            json!({ "name": name, "variablesReference": i + 1, "expensive": false })
+        })
+        .collect();
+    json!({ "scopes": scopes })
+}
+
+/// Variables of a scope, builtin functions are left out.
+fn variables(scope: &[(String, LoxValue)]) -> Value {
+    let variables: Vec<Value> = scope
+        .iter()
+        .filter(|(_, value)| !matches!(value, LoxValue::NativeFunction(_)))
+        .map(
+            |(name, value)| json!({ "name": name, "value": value.repr(), "variablesReference": 0 }),
+        )
+        .collect();
+    json!({ "variables": variables })
+}
+
+fn disconnected() -> RloxError {
+    RloxError::RuntimeError("Debugger disconnected.".to_owned())
+}
+
+impl<R: BufRead, W: Write> Hook for DebugHook<R, W> {
+    fn before_stmt(
+        &mut self,
+        stmt: &Stmt,
+        environment: &Environment,
+        calls: usize,
+    ) -> Result<(), RloxError> {
+        let mut session = self.session.borrow_mut();
+        let depth = Depth {
+            calls,
+            blocks: environment.depth(),
+        };
+        let Some(reason) = session.debugger.check(stmt.line(), depth) else {
+            return Ok(());
+        };
+        session.event(
+            "stopped",
+            json!({ "reason": reason.as_str(), "threadId": THREAD_ID, "allThreadsStopped": true }),
+        )?;
+
+        let chain = environment.scopes();
+        loop {
+            let Some(request) = session.read()? else {
+                session.disconnected = true;
+                return Err(disconnected());
+            };
+            let mode = match request["command"].as_str().unwrap_or_default() {
+                "stackTrace" => {
+                    session.respond(&request, self.stack_trace(stmt))?;
+                    continue;
+                }
+                "scopes" => {
+                    session.respond(&request, scopes(&chain))?;
+                    continue;
+                }
+                "variables" => {
+                    let reference = request["arguments"]["variablesReference"]
+                        .as_u64()
+                        .unwrap_or_default() as usize;
+                    match chain.get(reference.wrapping_sub(1)) {
+                        Some(scope) => session.respond(&request, variables(scope))?,
+                        None => session.fail(&request, "Unknown variables reference.")?,
+                    }
+                    continue;
+                }
+                "continue" => StepMode::Continue,
+                "next" => StepMode::Over,
+                "stepIn" => StepMode::In,
+                "stepOut" => StepMode::Out,
+                "disconnect" => {
+                    session.respond(&request, json!({}))?;
+                    session.disconnected = true;
+                    return Err(disconnected());
+                }
+                _ => {
+                    session.common(&request)?;
+                    continue;
+                }
+            };
+            session.respond(&request, json!({ "allThreadsContinued": true }))?;
+            session.debugger.resume(mode, depth);
+            return Ok(());
+        }
+    }
+}
+
+/// Sends program output to the client as `output` events, line by line.
+struct OutputEvents<R, W> {
+    session: Rc<RefCell<Session<R, W>>>,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead, W: Write> OutputEvents<R, W> {
+    fn emit(&mut self, end: usize) -> io::Result<()> {
+        let output: Vec<u8> = self.buffer.drain(..end).collect();
+        self.session.borrow_mut().event(
+            "output",
+            json!({ "category": "stdout", "output": String::from_utf8_lossy(&output) }),
+        )
+    }
+}
+
+impl<R: BufRead, W: Write> Write for OutputEvents<R, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if let Some(end) = self.buffer.iter().rposition(|&b| b == b'\n') {
+            self.emit(end + 1)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.emit(self.buffer.len())?;
+        }
+        Ok(())
+    }
+}
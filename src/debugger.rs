@@ -0,0 +1,107 @@
+use std::collections::BTreeSet;
+
+/// How execution continues after the debugger resumes.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum StepMode {
+    /// Run until the next breakpoint.
+    #[default]
+    Continue,
+    /// Stop at the next statement.
+    In,
+    /// Stop at the next statement not nested deeper than the current one.
+    Over,
+    /// Stop at the next statement outside the current block or function.
+    Out,
+}
+
+/// Why the debugger paused execution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    Entry,
+    Breakpoint,
+    Step,
+}
+
+impl StopReason {
+    /// Name of the reason as used by the Debug Adapter Protocol.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopReason::Entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+        }
+    }
+}
+
+/// How deep a statement runs, call frames first.
+///
+/// A function body runs in the scope of its closure, which can be shallower
+/// than its caller's, so block nesting only compares within the same call.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Depth {
+    /// Function calls in progress.
+    pub calls: usize,
+    /// Scopes enclosing the statement.
+    pub blocks: usize,
+}
+
+/// Breakpoint and stepping state, consulted before each statement.
+///
+/// Stepping over a call or a block runs it as a whole, and stepping out runs
+/// until the enclosing block or the caller continues.
+#[derive(Debug, Default)]
+pub struct Debugger {
+    breakpoints: BTreeSet<usize>,
+    mode: StepMode,
+    /// Depth where the last step was requested.
+    depth: Depth,
+    /// Pause before the first statement.
+    pub stop_on_entry: bool,
+    /// Line of the previous statement, a breakpoint fires once per line.
+    last_line: usize,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace all breakpoints with the given lines.
+    pub fn set_breakpoints(&mut self, lines: impl IntoIterator<Item = usize>) {
+        self.breakpoints = lines.into_iter().collect();
+    }
+
+    pub fn breakpoints(&self) -> &BTreeSet<usize> {
+        &self.breakpoints
+    }
+
+    /// Resume execution from a pause at the given depth.
+    pub fn resume(&mut self, mode: StepMode, depth: Depth) {
+        self.mode = mode;
+        self.depth = depth;
+    }
+
+    /// Decide whether to pause before a statement at `line` and `depth`.
+    pub fn check(&mut self, line: usize, depth: Depth) -> Option<StopReason> {
+        let new_line = line != self.last_line;
+        self.last_line = line;
+
+        if self.stop_on_entry {
+            self.stop_on_entry = false;
+            return Some(StopReason::Entry);
+        }
+        let stepped = match self.mode {
+            StepMode::Continue => false,
+            StepMode::In => true,
+            StepMode::Over => depth <= self.depth,
+            StepMode::Out => depth < self.depth,
+        };
+        if stepped {
+            return Some(StopReason::Step);
+        }
+        if new_line && self.breakpoints.contains(&line) {
+            return Some(StopReason::Breakpoint);
+        }
+        None
+    }
+}
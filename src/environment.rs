@@ -29,6 +29,14 @@ impl Environment {
         }
     }
 
+    /// Share the current scope, so that variables defined or assigned through
+    /// either environment are seen by the other.
+    pub fn capture(&self) -> Self {
+        Self {
+            value: Rc::clone(&self.value),
+        }
+    }
+
     /// Define a variable in current scope.
     pub fn define(&mut self, name: &str, value: LoxValue) {
         self.value
@@ -82,6 +90,40 @@ impl Environment {
         )))
     }
 
+    /// Number of scopes entered above the global scope.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut current = Rc::clone(&self.value);
+        loop {
+            let parent = current.borrow().parent.clone();
+            match parent {
+                Some(parent) => {
+                    depth += 1;
+                    current = parent;
+                }
+                None => return depth,
+            }
+        }
+    }
+
+    /// Variables of every scope sorted by name, innermost scope first.
+    pub fn scopes(&self) -> Vec<Vec<(String, LoxValue)>> {
+        let mut scopes = vec![];
+        let mut current = Some(Rc::clone(&self.value));
+        while let Some(scope) = current {
+            let scope = scope.borrow();
+            let mut variables: Vec<(String, LoxValue)> = scope
+                .table
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            variables.sort_by(|a, b| a.0.cmp(&b.0));
+            scopes.push(variables);
+            current = scope.parent.clone();
+        }
+        scopes
+    }
+
     /// Enter a new inner scope.
     pub fn enter_scope(&mut self) {
         self.value = Rc::new(RefCell::new(EnvInner {
@@ -1,8 +1,18 @@
 use std::{
     fmt::{self},
     io,
+    time::Duration,
 };
 
+/// Why a running program was stopped from outside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+    /// Stopped through an `InterruptHandle`.
+    Cancelled,
+    /// Ran longer than `Limits::timeout`.
+    TimedOut(Duration),
+}
+
 #[derive(Debug)]
 pub enum RloxError {
     /// Convert from std::io::Error.
@@ -13,8 +23,43 @@ pub enum RloxError {
     ScannerError,
     /// Syntax error during parsing.
     SyntaxError(usize, String, String),
+    /// Error during parsing tokens.
+    ParserError,
     /// Runtime error.
     RuntimeError(String),
+    /// More steps executed than allowed by `Limits::max_steps`.
+    StepLimitExceeded(u64),
+    /// Deeper nesting than allowed by `Limits::max_depth`.
+    DepthLimitExceeded(usize),
+    /// More string bytes created than allowed by `Limits::max_string_bytes`.
+    MemoryLimitExceeded(usize),
+    /// Execution stopped by the host.
+    Interrupted(Interruption),
+    /// The program called `exit()` with this status, for the host to act on.
+    Exit(i32),
+}
+
+/// `io::Error` can't be cloned, so a cloned one keeps its kind and message.
+impl Clone for RloxError {
+    fn clone(&self) -> Self {
+        match self {
+            RloxError::IOError(e) => RloxError::IOError(io::Error::new(e.kind(), e.to_string())),
+            RloxError::LexicalError(line, near, message) => {
+                RloxError::LexicalError(*line, near.clone(), message.clone())
+            }
+            RloxError::ScannerError => RloxError::ScannerError,
+            RloxError::SyntaxError(line, near, message) => {
+                RloxError::SyntaxError(*line, near.clone(), message.clone())
+            }
+            RloxError::ParserError => RloxError::ParserError,
+            RloxError::RuntimeError(message) => RloxError::RuntimeError(message.clone()),
+            RloxError::StepLimitExceeded(max) => RloxError::StepLimitExceeded(*max),
+            RloxError::DepthLimitExceeded(max) => RloxError::DepthLimitExceeded(*max),
+            RloxError::MemoryLimitExceeded(max) => RloxError::MemoryLimitExceeded(*max),
+            RloxError::Interrupted(interruption) => RloxError::Interrupted(*interruption),
+            RloxError::Exit(status) => RloxError::Exit(*status),
+        }
+    }
 }
 
 impl From<io::Error> for RloxError {
@@ -36,7 +81,27 @@ impl fmt::Display for RloxError {
                 f,
                 "Syntax Error: [line: {line}, near: {near}, message: {message}]."
             ),
+            RloxError::ParserError => write!(f, "Parser Error."),
             RloxError::RuntimeError(message) => write!(f, "Runtime error: {}", message),
+            RloxError::StepLimitExceeded(max) => {
+                write!(f, "Runtime error: Step limit of {max} exceeded.")
+            }
+            RloxError::DepthLimitExceeded(max) => {
+                write!(f, "Runtime error: Depth limit of {max} exceeded.")
+            }
+            RloxError::MemoryLimitExceeded(max) => {
+                write!(
+                    f,
+                    "Runtime error: String memory limit of {max} bytes exceeded."
+                )
+            }
+            RloxError::Interrupted(Interruption::Cancelled) => {
+                write!(f, "Runtime error: Interrupted.")
+            }
+            RloxError::Interrupted(Interruption::TimedOut(timeout)) => {
+                write!(f, "Runtime error: Timed out after {timeout:?}.")
+            }
+            RloxError::Exit(status) => write!(f, "Exited with status {status}."),
         }
     }
 }
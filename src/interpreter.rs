@@ -1,52 +1,698 @@
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    ops::RangeInclusive,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
 use crate::{
     ast::{
         expr::{self, Expr},
         stmt::{self, Stmt},
     },
+    bignum::{BigInt, Decimal},
     environment::Environment,
-    error::RloxError,
+    error::{Interruption, RloxError},
+    native::{self, Capabilities},
     token::{LiteralType, Token, TokenType},
-    value::LoxValue,
+    value::{List, LoxFunction, LoxValue, Map, MapKey, float_to_int},
 };
 
-#[derive(Debug, Default)]
+/// Observer of execution installed on an `Interpreter`, e.g. a debugger.
+pub trait Hook {
+    /// Called before each statement is executed, with the number of function
+    /// calls in progress. Returning an error aborts execution.
+    fn before_stmt(
+        &mut self,
+        _stmt: &Stmt,
+        _environment: &Environment,
+        _calls: usize,
+    ) -> Result<(), RloxError> {
+        Ok(())
+    }
+
+    /// Called once a statement has been executed, whether or not it succeeded.
+    fn after_stmt(&mut self, _stmt: &Stmt, _environment: &Environment) -> Result<(), RloxError> {
+        Ok(())
+    }
+
+    /// Called when a condition decides which branch is taken.
+    fn branch(&mut self, _condition: &Expr, _taken: bool) -> Result<(), RloxError> {
+        Ok(())
+    }
+
+    /// Called with the value of each expression once evaluated.
+    fn after_expr(
+        &mut self,
+        _expr: &Expr,
+        _value: &LoxValue,
+        _environment: &Environment,
+    ) -> Result<(), RloxError> {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for dyn Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<hook>")
+    }
+}
+
+/// Nesting depth allowed by default, within the stack of the main thread even
+/// for debug builds.
+pub const DEFAULT_MAX_DEPTH: usize = 1536;
+
+/// Resources a program may use, `None` meaning unlimited.
+///
+/// Limits apply over the lifetime of an `Interpreter`, except for the timeout
+/// which applies to each call to `execute`. Exceeding one aborts execution
+/// with a dedicated error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Statements and expressions executed.
+    pub max_steps: Option<u64>,
+    /// Statements and expressions being executed inside each other. A call
+    /// nests its function's body in its statement and expressions, so each
+    /// level of recursion takes two or more: by default, `return f(n - 1);`
+    /// recurses about 760 times and `return f(n - 1) + 1;` about 500 times.
+    pub max_depth: Option<usize>,
+    /// Bytes of strings created by literals and concatenation.
+    pub max_string_bytes: Option<usize>,
+    /// Wall-clock time a program may run, checked between statements.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_steps: None,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            max_string_bytes: None,
+            timeout: None,
+        }
+    }
+}
+
+/// Handle stopping an `Interpreter` from another thread.
+///
+/// The interpreter stops before its next statement with `RloxError::Interrupted`.
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    /// Request the interpreter to stop.
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Progress of a `for-in` loop through the iterated value.
+///
+/// Strings, lists, maps and ranges are iterated natively. A map holding an
+/// `iter` function is iterated through the map that `iter()` returns, whose
+/// `next()` is called for each value until it returns nil.
+enum Iteration {
+    /// Characters of a string.
+    Chars(std::vec::IntoIter<char>),
+    /// Elements of a list by index, seeing changes made while looping.
+    Elements(List, usize),
+    /// Keys of a map, as they were when the loop started.
+    Keys(std::vec::IntoIter<LoxValue>),
+    /// Next integer and excluded end of a range.
+    IntRange(i64, i64),
+    /// Float range as its start, excluded end, index of the next value and
+    /// value before it. Values are `start + index`, as adding one to a float
+    /// beyond 2^53 doesn't change it.
+    Range {
+        start: f64,
+        end: f64,
+        index: u64,
+        previous: f64,
+    },
+    /// `next` function of the iterator returned by a map's `iter` function.
+    Iterator(LoxValue),
+}
+
+/// How control leaves an executed statement.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Flow {
+    /// Go on with the next statement.
+    Normal,
+    /// Leave the innermost loop.
+    Break,
+    /// Skip to the next iteration of the innermost loop.
+    Continue,
+    /// Leave the innermost function with a value.
+    Return(LoxValue),
+}
+
+#[derive(Debug)]
 pub struct Interpreter {
     pub had_error: bool,
     pub environment: Environment,
+    hook: Option<Box<dyn Hook>>,
+    /// Where `print` writes to, stdout by default.
+    output: Output,
+    /// Where `eprint` and uncaught errors write to, stderr by default.
+    error_output: Output,
+    /// Where `readLine` reads from, stdin if not set.
+    input: Option<Input>,
+    /// Command line arguments of the script.
+    args: Vec<String>,
+    limits: Limits,
+    steps: u64,
+    depth: usize,
+    /// Function calls in progress, reported to the hook.
+    calls: usize,
+    string_bytes: usize,
+    /// Set by `InterruptHandle`, cleared once the interruption is reported.
+    interrupted: InterruptHandle,
+    /// When the running program times out.
+    deadline: Option<Instant>,
+    /// Host resources natives may access.
+    capabilities: Capabilities,
+}
+
+/// Boxed writer, wrapped to be printable in `Debug` output.
+struct Output(Box<dyn Write>);
+
+impl fmt::Debug for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<output>")
+    }
+}
+
+/// Boxed reader, wrapped to be printable in `Debug` output.
+struct Input(Box<dyn BufRead>);
+
+impl fmt::Debug for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<input>")
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
+    /// Create an interpreter with every capability, for trusted scripts.
     pub fn new() -> Self {
+        Self::with_capabilities(Capabilities::all())
+    }
+
+    /// Create an interpreter whose natives may only use the given capabilities.
+    pub fn with_capabilities(capabilities: Capabilities) -> Self {
+        let mut environment = Environment::new();
+        native::define_globals(&mut environment);
         Self {
             had_error: false,
-            environment: Environment::new(),
+            environment,
+            hook: None,
+            output: Output(Box::new(io::stdout())),
+            error_output: Output(Box::new(io::stderr())),
+            input: None,
+            args: vec![],
+            limits: Limits::default(),
+            steps: 0,
+            depth: 0,
+            calls: 0,
+            string_bytes: 0,
+            interrupted: InterruptHandle::default(),
+            deadline: None,
+            capabilities,
+        }
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Install a hook called while executing.
+    pub fn set_hook(&mut self, hook: Box<dyn Hook>) {
+        self.hook = Some(hook);
+    }
+
+    /// Redirect output of `print`.
+    pub fn set_output(&mut self, output: Box<dyn Write>) {
+        self.output = Output(output);
+    }
+
+    /// Restrict resources used by programs.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Redirect output of `eprint` and uncaught errors.
+    pub fn set_error_output(&mut self, output: Box<dyn Write>) {
+        self.error_output = Output(output);
+    }
+
+    /// Read input of `readLine` from the given reader instead of stdin.
+    pub fn set_input(&mut self, input: Box<dyn BufRead>) {
+        self.input = Some(Input(input));
+    }
+
+    /// Set the command line arguments returned by `args`.
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = args;
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Read a line of input without its line terminator, `None` at end of input.
+    pub(crate) fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        let len = match &mut self.input {
+            Some(input) => input.0.read_line(&mut line)?,
+            None => io::stdin().read_line(&mut line)?,
+        };
+        if len == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+
+    /// Writer of `eprint`.
+    pub(crate) fn error_output(&mut self) -> &mut dyn Write {
+        &mut self.error_output.0
+    }
+
+    /// Flush output written by `print`.
+    pub fn flush_output(&mut self) -> io::Result<()> {
+        self.output.0.flush()
+    }
+
+    /// Return a handle interrupting this interpreter from another thread.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupted.clone()
+    }
+
+    /// Fail if the program was interrupted or ran out of time.
+    fn check_interrupt(&mut self) -> Result<(), RloxError> {
+        if self.interrupted.0.swap(false, Ordering::Relaxed) {
+            return Err(RloxError::Interrupted(Interruption::Cancelled));
+        }
+        if let (Some(deadline), Some(timeout)) = (self.deadline, self.limits.timeout)
+            && Instant::now() >= deadline
+        {
+            return Err(RloxError::Interrupted(Interruption::TimedOut(timeout)));
+        }
+        Ok(())
+    }
+
+    /// Count a step one level deeper, failing if it exceeds a limit.
+    fn enter(&mut self) -> Result<(), RloxError> {
+        self.steps += 1;
+        if let Some(max) = self.limits.max_steps
+            && self.steps > max
+        {
+            return Err(RloxError::StepLimitExceeded(max));
+        }
+        if let Some(max) = self.limits.max_depth
+            && self.depth >= max
+        {
+            return Err(RloxError::DepthLimitExceeded(max));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Create a string value, failing if it exceeds the string memory limit.
+    pub(crate) fn new_string(&mut self, s: String) -> Result<LoxValue, RloxError> {
+        self.string_bytes += s.len();
+        if let Some(max) = self.limits.max_string_bytes
+            && self.string_bytes > max
+        {
+            return Err(RloxError::MemoryLimitExceeded(max));
+        }
+        Ok(LoxValue::String(s))
+    }
+
+    /// Execute a single statement and hand any runtime error back to the caller.
+    pub fn execute(&mut self, stmt: &Stmt) -> Result<(), RloxError> {
+        // The parser keeps `break`, `continue` and `return` inside loops and functions,
+        // so no signal gets here.
+        self.run(stmt).map(|_| ())
+    }
+
+    /// Execute a statement, returning how control leaves it.
+    fn run(&mut self, stmt: &Stmt) -> Result<Flow, RloxError> {
+        if self.depth == 0 {
+            self.deadline = self.limits.timeout.map(|timeout| Instant::now() + timeout);
+        }
+        self.check_interrupt()?;
+        self.enter()?;
+        let result = self.execute_hooked(stmt);
+        self.depth -= 1;
+        result
+    }
+
+    /// Run statements in order until one errors or leaves with a control flow signal.
+    fn run_statements(&mut self, statements: &[Stmt]) -> Result<Flow, RloxError> {
+        for stmt in statements {
+            let flow = self.run(stmt)?;
+            if flow != Flow::Normal {
+                return Ok(flow);
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    /// Call a native or lox function.
+    fn call(&mut self, callee: LoxValue, arguments: Vec<LoxValue>) -> Result<LoxValue, RloxError> {
+        match callee {
+            LoxValue::NativeFunction(native) => {
+                if !native.arity.contains(&arguments.len()) {
+                    return Err(RloxError::RuntimeError(format!(
+                        "{} expected {} arguments but got {}.",
+                        native.name,
+                        arity_to_string(&native.arity),
+                        arguments.len()
+                    )));
+                }
+                native.call(self, &arguments)
+            }
+            LoxValue::Function(function) => {
+                if function.params.len() != arguments.len() {
+                    return Err(RloxError::RuntimeError(format!(
+                        "Expected {} arguments but got {}.",
+                        function.params.len(),
+                        arguments.len()
+                    )));
+                }
+                self.call_function(&function, arguments)
+            }
+            _ => Err(RloxError::RuntimeError(
+                "Can only call functions.".to_owned(),
+            )),
+        }
+    }
+
+    /// Call a lox function in a new scope of its closure.
+    fn call_function(
+        &mut self,
+        function: &LoxFunction,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RloxError> {
+        let caller = std::mem::replace(&mut self.environment, function.closure.capture());
+        self.environment.enter_scope();
+        for (param, argument) in function.params.iter().zip(arguments) {
+            self.environment.define(&param.lexeme, argument);
+        }
+        self.calls += 1;
+        let result = match function.body.as_ref() {
+            Stmt::Block(statements, ..) => self.run_statements(statements),
+            body => self.run(body),
+        };
+        // The caller scope is restored whether or not the body failed.
+        self.calls -= 1;
+        self.environment = caller;
+        match result? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(LoxValue::Nil),
+        }
+    }
+
+    /// Start iterating a value.
+    ///
+    /// A map with a function under `"iter"` is iterable: each loop calls it for
+    /// a fresh iterator, a map whose `"next"` function returns values until nil.
+    /// Other maps are iterated by key.
+    fn iteration(&mut self, value: LoxValue) -> Result<Iteration, RloxError> {
+        Ok(match value {
+            LoxValue::String(s) => Iteration::Chars(s.chars().collect::<Vec<_>>().into_iter()),
+            LoxValue::List(list) => Iteration::Elements(list, 0),
+            LoxValue::Map(map) => {
+                let iter = function_entry(&map.borrow(), "iter");
+                match iter {
+                    Some(iter) => {
+                        let iterator = self.call(iter, vec![])?;
+                        let next = match &iterator {
+                            LoxValue::Map(iterator) => function_entry(&iterator.borrow(), "next"),
+                            _ => None,
+                        };
+                        match next {
+                            Some(next) => Iteration::Iterator(next),
+                            None => {
+                                return Err(RloxError::RuntimeError(format!(
+                                    "iter() must return a map with a 'next' function, got {}.",
+                                    iterator.type_name()
+                                )));
+                            }
+                        }
+                    }
+                    None => {
+                        let keys: Vec<LoxValue> =
+                            map.borrow().iter().map(|(key, _)| key.to_value()).collect();
+                        Iteration::Keys(keys.into_iter())
+                    }
+                }
+            }
+            LoxValue::IntRange(start, end) => Iteration::IntRange(start, end),
+            LoxValue::Range(start, end) => Iteration::Range {
+                start,
+                end,
+                index: 0,
+                previous: f64::NEG_INFINITY,
+            },
+            value => {
+                return Err(RloxError::RuntimeError(format!(
+                    "Can only iterate over strings, lists, maps, ranges and iterators, got {}.",
+                    value.type_name()
+                )));
+            }
+        })
+    }
+
+    /// Return the next value of an iteration, or `None` once done.
+    fn next_value(&mut self, iteration: &mut Iteration) -> Result<Option<LoxValue>, RloxError> {
+        Ok(match iteration {
+            Iteration::Chars(chars) => match chars.next() {
+                Some(c) => Some(self.new_string(c.to_string())?),
+                None => None,
+            },
+            Iteration::Elements(list, i) => {
+                let element = list.borrow().get(*i).cloned();
+                *i += 1;
+                element
+            }
+            Iteration::Keys(keys) => keys.next(),
+            Iteration::IntRange(next, end) => {
+                let value = *next;
+                *next = next.saturating_add(1);
+                (value < *end).then_some(LoxValue::Int(value))
+            }
+            Iteration::Range {
+                start,
+                end,
+                index,
+                previous,
+            } => {
+                let value = *start + *index as f64;
+                // Also stop once values are too large to keep increasing, or NaN.
+                if !(value < *end && value > *previous) {
+                    None
+                } else {
+                    *index += 1;
+                    *previous = value;
+                    Some(LoxValue::Number(value))
+                }
+            }
+            Iteration::Iterator(next) => match self.call(next.clone(), vec![])? {
+                LoxValue::Nil => None,
+                value => Some(value),
+            },
+        })
+    }
+
+    /// Apply a binary operator, or the one of a compound assignment such as `+=`.
+    fn binary(
+        &mut self,
+        operator: &Token,
+        lhs: LoxValue,
+        rhs: LoxValue,
+    ) -> Result<LoxValue, RloxError> {
+        match operator.token_type {
+            TokenType::EqualEqual => Ok(LoxValue::Bool(lhs == rhs)),
+            TokenType::BangEqual => Ok(LoxValue::Bool(lhs != rhs)),
+            TokenType::Plus | TokenType::PlusEqual => match (lhs, rhs) {
+                (LoxValue::String(lhs), LoxValue::String(rhs)) => {
+                    self.new_string(format!("{}{}", lhs, rhs))
+                }
+                (lhs, rhs) => match numbers(&lhs, &rhs)? {
+                    Some(numbers) => arithmetic(operator, numbers),
+                    None => Err(RloxError::RuntimeError(
+                        "Operands must be two numbers or strings.".to_owned(),
+                    )),
+                },
+            },
+            TokenType::Ampersand
+            | TokenType::Pipe
+            | TokenType::Caret
+            | TokenType::LessLess
+            | TokenType::GreaterGreater => {
+                let lhs = integer(&lhs, operator)?;
+                let rhs = integer(&rhs, operator)?;
+                let value = match operator.token_type {
+                    TokenType::Ampersand => lhs & rhs,
+                    TokenType::Pipe => lhs | rhs,
+                    TokenType::Caret => lhs ^ rhs,
+                    // Bits shifted past either end are dropped, `>>` keeps the sign.
+                    _ if !(0..64).contains(&rhs) => {
+                        return Err(RloxError::RuntimeError(format!(
+                            "Shift amount must be between 0 and 63, got {rhs}."
+                        )));
+                    }
+                    TokenType::LessLess => lhs << rhs,
+                    _ => lhs >> rhs,
+                };
+                Ok(LoxValue::Int(value))
+            }
+            // Exact, so a float isn't ordered against a rounded big integer or decimal.
+            TokenType::Greater
+            | TokenType::GreaterEqual
+            | TokenType::Less
+            | TokenType::LessEqual
+                if is_float_mix(&lhs, &rhs) =>
+            {
+                Ok(match lhs.exact_cmp(&rhs) {
+                    Some(ordering) => compare(operator.token_type, ordering),
+                    None => LoxValue::Bool(false),
+                })
+            }
+            TokenType::Minus
+            | TokenType::MinusEqual
+            | TokenType::Star
+            | TokenType::StarEqual
+            | TokenType::Slash
+            | TokenType::SlashEqual
+            | TokenType::Percent
+            | TokenType::TildeSlash
+            | TokenType::StarStar
+            | TokenType::Greater
+            | TokenType::GreaterEqual
+            | TokenType::Less
+            | TokenType::LessEqual
+            | TokenType::DotDot => match numbers(&lhs, &rhs)? {
+                Some(numbers) => arithmetic(operator, numbers),
+                None => Err(RloxError::RuntimeError(
+                    "Operands must be two numbers.".to_owned(),
+                )),
+            },
+            _ => Err(RloxError::RuntimeError(format!(
+                "Unsupported binary operator '{}'.",
+                operator.lexeme
+            ))),
+        }
+    }
+
+    /// Replace the value of a variable or index target by `update` of it, evaluating
+    /// the target's object and index once, and return the result of `update`.
+    fn update_target(
+        &mut self,
+        target: &Expr,
+        update: impl FnOnce(&mut Self, LoxValue) -> Result<(LoxValue, LoxValue), RloxError>,
+    ) -> Result<LoxValue, RloxError> {
+        match target {
+            Expr::Variable { name } => {
+                let current = self.environment.get(name)?;
+                let (value, result) = update(self, current)?;
+                self.environment.assign(name, value)?;
+                Ok(result)
+            }
+            Expr::Index { object, index, .. } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                let current = get_index(&object, &index)?;
+                let (value, result) = update(self, current)?;
+                set_index(&object, &index, value)?;
+                Ok(result)
+            }
+            _ => Err(RloxError::RuntimeError(
+                "Invalid assignment target.".to_owned(),
+            )),
+        }
+    }
+
+    fn execute_hooked(&mut self, stmt: &Stmt) -> Result<Flow, RloxError> {
+        // A program is not a statement of its own, hooks only see its declarations.
+        let hooked = self.hook.is_some() && !matches!(stmt, Stmt::Program(_));
+        if hooked && let Some(hook) = self.hook.as_mut() {
+            hook.before_stmt(stmt, &self.environment, self.calls)?;
+        }
+        let result = stmt.accept(self);
+        if hooked && let Some(hook) = self.hook.as_mut() {
+            hook.after_stmt(stmt, &self.environment)?;
         }
+        result
     }
 
-    pub fn interpret(&mut self, program: Stmt) {
+    /// Evaluate an expression, reporting its value to the hook.
+    pub fn evaluate(&mut self, expr: &Expr) -> Result<LoxValue, RloxError> {
+        self.enter()?;
+        let result = self.evaluate_hooked(expr);
+        self.depth -= 1;
+        result
+    }
+
+    fn evaluate_hooked(&mut self, expr: &Expr) -> Result<LoxValue, RloxError> {
+        let value = expr.accept(self)?;
+        if let Some(hook) = self.hook.as_mut() {
+            hook.after_expr(expr, &value, &self.environment)?;
+        }
+        Ok(value)
+    }
+
+    /// Run a program, reporting its error to the error output. Only a call to
+    /// `exit()` is returned, as `RloxError::Exit`, for the host to act on.
+    pub fn interpret(&mut self, program: Stmt) -> Result<(), RloxError> {
         self.had_error = false;
         if let Stmt::Program(_) = program {
-            if let Err(e) = program.accept(self) {
-                eprintln!("{}", e);
+            match self.execute(&program) {
+                Err(e @ RloxError::Exit(_)) => return Err(e),
+                Err(e) => {
+                    let _ = writeln!(self.error_output.0, "{}", e);
+                }
+                Ok(()) => {}
             }
         } else {
             println!("Input is not a valid program!");
             self.had_error = true;
         }
+        Ok(())
     }
 }
 
 /// Visitor for expression.
 impl expr::Visitor<Result<LoxValue, RloxError>> for Interpreter {
     fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> Result<LoxValue, RloxError> {
-        let value = value.accept(self)?;
+        let value = self.evaluate(value)?;
         self.environment.assign(name, value.clone())?;
         Ok(value)
     }
 
     fn visit_literal(&mut self, value: &LiteralType) -> Result<LoxValue, RloxError> {
         Ok(match value {
-            LiteralType::String(s) => LoxValue::String(s.clone()),
+            LiteralType::String(s) => return self.new_string(s.clone()),
+            LiteralType::Int(n) => LoxValue::Int(*n),
+            LiteralType::BigInt(n) => LoxValue::BigInt(n.clone()),
+            LiteralType::Decimal(n) => LoxValue::Decimal(n.clone()),
             LiteralType::Number(num) => LoxValue::Number(*num),
             LiteralType::Bool(b) => LoxValue::Bool(*b),
             LiteralType::Nil => LoxValue::Nil,
@@ -54,22 +700,26 @@ impl expr::Visitor<Result<LoxValue, RloxError>> for Interpreter {
     }
 
     fn visit_grouping(&mut self, expression: &Expr) -> Result<LoxValue, RloxError> {
-        expression.accept(self)
+        self.evaluate(expression)
     }
 
     fn visit_unary(&mut self, operator: &Token, right: &Expr) -> Result<LoxValue, RloxError> {
-        let right = right.accept(self)?;
+        let right = self.evaluate(right)?;
         match operator.token_type {
-            TokenType::Minus => {
-                if let LoxValue::Number(n) = right {
-                    Ok(LoxValue::Number(-n))
-                } else {
-                    Err(RloxError::RuntimeError(
-                        "Operand must be a number".to_owned(),
-                    ))
-                }
-            }
+            TokenType::Minus => match right {
+                LoxValue::Int(n) => n
+                    .checked_neg()
+                    .map(LoxValue::Int)
+                    .ok_or_else(|| overflow(operator)),
+                LoxValue::BigInt(n) => Ok(LoxValue::BigInt(-&n)),
+                LoxValue::Decimal(n) => Ok(LoxValue::Decimal(-&n)),
+                LoxValue::Number(n) => Ok(LoxValue::Number(-n)),
+                _ => Err(RloxError::RuntimeError(
+                    "Operand must be a number".to_owned(),
+                )),
+            },
             TokenType::Bang => Ok(LoxValue::Bool(!right.is_truthy())),
+            TokenType::Tilde => Ok(LoxValue::Int(!integer(&right, operator)?)),
             _ => Err(RloxError::RuntimeError(
                 "Unknown unary operator.".to_owned(),
             )),
@@ -82,73 +732,23 @@ impl expr::Visitor<Result<LoxValue, RloxError>> for Interpreter {
         operator: &Token,
         right: &Expr,
     ) -> Result<LoxValue, RloxError> {
-        let lhs = left.accept(self)?;
-        let rhs = right.accept(self)?;
+        let lhs = self.evaluate(left)?;
+        let rhs = self.evaluate(right)?;
+        self.binary(operator, lhs, rhs)
+    }
 
-        match operator.token_type {
-            TokenType::Plus => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => Ok(LoxValue::Number(lhs + rhs)),
-                (LoxValue::String(lhs), LoxValue::String(rhs)) => {
-                    Ok(LoxValue::String(format!("{}{}", lhs, rhs)))
-                }
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers or strings.".to_owned(),
-                )),
-            },
-            TokenType::Minus => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => Ok(LoxValue::Number(lhs - rhs)),
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers.".to_owned(),
-                )),
-            },
-            TokenType::Star => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => Ok(LoxValue::Number(lhs * rhs)),
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers.".to_owned(),
-                )),
-            },
-            TokenType::Slash => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => {
-                    if rhs == 0.0 {
-                        Err(RloxError::RuntimeError(
-                            "can not divided by zero".to_owned(),
-                        ))
-                    } else {
-                        Ok(LoxValue::Number(lhs / rhs))
-                    }
-                }
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers.".to_owned(),
-                )),
-            },
-            TokenType::Greater => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => Ok(LoxValue::Bool(lhs > rhs)),
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers.".to_owned(),
-                )),
-            },
-            TokenType::GreaterEqual => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => Ok(LoxValue::Bool(lhs >= rhs)),
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers.".to_owned(),
-                )),
-            },
-            TokenType::Less => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => Ok(LoxValue::Bool(lhs < rhs)),
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers.".to_owned(),
-                )),
-            },
-            TokenType::LessEqual => match (lhs, rhs) {
-                (LoxValue::Number(lhs), LoxValue::Number(rhs)) => Ok(LoxValue::Bool(lhs <= rhs)),
-                _ => Err(RloxError::RuntimeError(
-                    "Operands must be two numbers.".to_owned(),
-                )),
-            },
-            TokenType::EqualEqual => Ok(LoxValue::Bool(lhs == rhs)),
-            TokenType::BangEqual => Ok(LoxValue::Bool(lhs != rhs)),
-            _ => unimplemented!("operator: {:?} not support yet", operator),
+    fn visit_call(
+        &mut self,
+        callee: &Expr,
+        _paren: &Token,
+        arguments: &[Expr],
+    ) -> Result<LoxValue, RloxError> {
+        let callee = self.evaluate(callee)?;
+        let mut values = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            values.push(self.evaluate(argument)?);
         }
+        self.call(callee, values)
     }
 
     fn visit_ternary(
@@ -157,13 +757,16 @@ impl expr::Visitor<Result<LoxValue, RloxError>> for Interpreter {
         truepart: &Expr,
         falsepart: &Expr,
     ) -> Result<LoxValue, RloxError> {
-        let condition = condition.accept(self)?;
-        match condition {
+        let value = self.evaluate(condition)?;
+        match value {
             LoxValue::Bool(b) => {
+                if let Some(hook) = self.hook.as_mut() {
+                    hook.branch(condition, b)?;
+                }
                 if b {
-                    truepart.accept(self)
+                    self.evaluate(truepart)
                 } else {
-                    falsepart.accept(self)
+                    self.evaluate(falsepart)
                 }
             }
             _ => Err(RloxError::RuntimeError(
@@ -175,47 +778,568 @@ impl expr::Visitor<Result<LoxValue, RloxError>> for Interpreter {
     fn visit_variable(&mut self, name: &Token) -> Result<LoxValue, RloxError> {
         self.environment.get(name)
     }
+
+    fn visit_list(&mut self, _bracket: &Token, elements: &[Expr]) -> Result<LoxValue, RloxError> {
+        let mut values = Vec::with_capacity(elements.len());
+        for element in elements {
+            values.push(self.evaluate(element)?);
+        }
+        Ok(LoxValue::list(values))
+    }
+
+    fn visit_map(
+        &mut self,
+        _brace: &Token,
+        entries: &[(Expr, Expr)],
+    ) -> Result<LoxValue, RloxError> {
+        let mut map = Map::default();
+        for (key, value) in entries {
+            let key = self.evaluate(key)?;
+            let key = MapKey::try_from(&key)?;
+            map.insert(key, self.evaluate(value)?);
+        }
+        Ok(LoxValue::map(map))
+    }
+
+    fn visit_index(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+    ) -> Result<LoxValue, RloxError> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        get_index(&object, &index)
+    }
+
+    fn visit_index_set(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<LoxValue, RloxError> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        let value = self.evaluate(value)?;
+        set_index(&object, &index, value.clone())?;
+        Ok(value)
+    }
+
+    fn visit_compound_assignment(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<LoxValue, RloxError> {
+        self.update_target(target, |interpreter, current| {
+            let rhs = interpreter.evaluate(value)?;
+            let value = interpreter.binary(operator, current, rhs)?;
+            Ok((value.clone(), value))
+        })
+    }
+
+    fn visit_update(
+        &mut self,
+        operator: &Token,
+        target: &Expr,
+        prefix: bool,
+    ) -> Result<LoxValue, RloxError> {
+        let step = match operator.token_type {
+            TokenType::PlusPlus => 1,
+            _ => -1,
+        };
+        self.update_target(target, |_, current| {
+            let value = match current {
+                LoxValue::Int(n) => n
+                    .checked_add(step)
+                    .map(LoxValue::Int)
+                    .ok_or_else(|| overflow(operator))?,
+                LoxValue::BigInt(ref n) => LoxValue::BigInt(n + &BigInt::from(step)),
+                LoxValue::Decimal(ref n) => LoxValue::Decimal(n + &Decimal::from(step)),
+                LoxValue::Number(n) => LoxValue::Number(n + step as f64),
+                _ => {
+                    return Err(RloxError::RuntimeError(format!(
+                        "Operand of '{}' must be a number.",
+                        operator.lexeme
+                    )));
+                }
+            };
+            if prefix {
+                Ok((value.clone(), value))
+            } else {
+                Ok((value, current))
+            }
+        })
+    }
+
+    fn visit_lambda(
+        &mut self,
+        _keyword: &Token,
+        params: &[Token],
+        body: &Rc<Stmt>,
+    ) -> Result<LoxValue, RloxError> {
+        Ok(LoxValue::Function(Rc::new(LoxFunction {
+            params: params.to_vec(),
+            body: Rc::clone(body),
+            closure: self.environment.capture(),
+        })))
+    }
 }
 
 /// Visitor for statement.
-impl stmt::Visitor<Result<(), RloxError>> for Interpreter {
-    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<(), RloxError> {
+impl stmt::Visitor<Result<Flow, RloxError>> for Interpreter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<Flow, RloxError> {
         self.environment.enter_scope();
-        for stmt in statements {
-            stmt.accept(self)?;
-        }
+        // Statements run apart so that the scope is left on every way out, errors included.
+        let result = self.run_statements(statements);
         self.environment.exit_scope();
-        Ok(())
+        result
     }
 
-    fn visit_program_stmt(&mut self, declarations: &[Stmt]) -> Result<(), RloxError> {
+    fn visit_program_stmt(&mut self, declarations: &[Stmt]) -> Result<Flow, RloxError> {
         for statement in declarations {
-            statement.accept(self)?;
+            self.run(statement)?;
         }
-        Ok(())
+        Ok(Flow::Normal)
     }
 
     fn visit_var_stmt(
         &mut self,
         name: &Token,
         initializer: &Option<Expr>,
-    ) -> Result<(), RloxError> {
+    ) -> Result<Flow, RloxError> {
         let mut value = LoxValue::Nil;
         if let Some(expr) = initializer {
-            value = expr.accept(self)?;
+            value = self.evaluate(expr)?;
         }
         self.environment.define(&name.lexeme, value);
-        Ok(())
+        Ok(Flow::Normal)
     }
 
-    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), RloxError> {
-        expression.accept(self)?;
-        Ok(())
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<Flow, RloxError> {
+        self.evaluate(expression)?;
+        Ok(Flow::Normal)
     }
 
-    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<(), RloxError> {
-        let value = expression.accept(self)?;
-        println!("{}", value);
-        Ok(())
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<Flow, RloxError> {
+        let value = self.evaluate(expression)?;
+        writeln!(self.output.0, "{}", value)?;
+        Ok(Flow::Normal)
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<Flow, RloxError> {
+        while self.evaluate(condition)?.is_truthy() {
+            match self.run(body)? {
+                Flow::Break => break,
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Normal | Flow::Continue => {}
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn visit_for_in_stmt(
+        &mut self,
+        name: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+    ) -> Result<Flow, RloxError> {
+        let value = self.evaluate(iterable)?;
+        let mut iteration = self.iteration(value)?;
+        while let Some(value) = self.next_value(&mut iteration)? {
+            // Each iteration has its own variable, which closures may capture.
+            self.environment.enter_scope();
+            self.environment.define(&name.lexeme, value);
+            let result = self.run(body);
+            self.environment.exit_scope();
+            match result? {
+                Flow::Break => break,
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Normal | Flow::Continue => {}
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn visit_if_stmt(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: Option<&Stmt>,
+    ) -> Result<Flow, RloxError> {
+        let taken = self.evaluate(condition)?.is_truthy();
+        if let Some(hook) = self.hook.as_mut() {
+            hook.branch(condition, taken)?;
+        }
+        match (taken, else_branch) {
+            (true, _) => self.run(then_branch),
+            (false, Some(else_branch)) => self.run(else_branch),
+            (false, None) => Ok(Flow::Normal),
+        }
+    }
+
+    fn visit_break_stmt(&mut self, _keyword: &Token) -> Result<Flow, RloxError> {
+        Ok(Flow::Break)
+    }
+
+    fn visit_continue_stmt(&mut self, _keyword: &Token) -> Result<Flow, RloxError> {
+        Ok(Flow::Continue)
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        _keyword: &Token,
+        value: &Option<Expr>,
+    ) -> Result<Flow, RloxError> {
+        let value = match value {
+            Some(expr) => self.evaluate(expr)?,
+            None => LoxValue::Nil,
+        };
+        Ok(Flow::Return(value))
+    }
+
+    fn visit_test_stmt(&mut self, _name: &Token, _body: &Stmt) -> Result<Flow, RloxError> {
+        // Test blocks are only run by the test runner.
+        Ok(Flow::Normal)
+    }
+}
+
+/// Read the element of a list or map at `index`.
+fn get_index(object: &LoxValue, index: &LoxValue) -> Result<LoxValue, RloxError> {
+    match object {
+        LoxValue::List(list) => {
+            let list = list.borrow();
+            let i = list_index(index, list.len())?;
+            Ok(list[i].clone())
+        }
+        LoxValue::Map(map) => {
+            let key = MapKey::try_from(index)?;
+            match map.borrow().get(&key) {
+                Some(value) => Ok(value.clone()),
+                None => Err(RloxError::RuntimeError(format!(
+                    "Undefined key {}.",
+                    index.repr()
+                ))),
+            }
+        }
+        _ => Err(not_indexable()),
+    }
+}
+
+/// Write the element of a list or map at `index`.
+fn set_index(object: &LoxValue, index: &LoxValue, value: LoxValue) -> Result<(), RloxError> {
+    match object {
+        LoxValue::List(list) => {
+            let mut list = list.borrow_mut();
+            let i = list_index(index, list.len())?;
+            list[i] = value;
+        }
+        LoxValue::Map(map) => {
+            let key = MapKey::try_from(index)?;
+            map.borrow_mut().insert(key, value);
+        }
+        _ => return Err(not_indexable()),
+    }
+    Ok(())
+}
+
+fn not_indexable() -> RloxError {
+    RloxError::RuntimeError("Can only index lists and maps.".to_owned())
+}
+
+/// Check that a value indexes an element of a list of length `len`.
+fn list_index(index: &LoxValue, len: usize) -> Result<usize, RloxError> {
+    match index {
+        LoxValue::Int(i) if *i >= 0 => {
+            let i = *i as usize;
+            if i < len {
+                Ok(i)
+            } else {
+                Err(RloxError::RuntimeError(format!(
+                    "Index {i} out of bounds for list of length {len}."
+                )))
+            }
+        }
+        _ => Err(RloxError::RuntimeError(format!(
+            "List index must be a non negative integer, got {}.",
+            index.repr()
+        ))),
+    }
+}
+
+/// Operands of an arithmetic operator, promoted to the wider type of the two:
+/// ints to big integers, both to decimals, and any but decimals to floats.
+enum Numbers {
+    Ints(i64, i64),
+    BigInts(BigInt, BigInt),
+    Decimals(Decimal, Decimal),
+    Floats(f64, f64),
+}
+
+/// Promote two numbers to a common type, `None` if either isn't a number.
+/// Decimals and floats don't mix, as decimals are meant to never round, but are
+/// compared exactly beforehand.
+fn numbers(lhs: &LoxValue, rhs: &LoxValue) -> Result<Option<Numbers>, RloxError> {
+    let numbers = match (lhs, rhs) {
+        (LoxValue::Int(lhs), LoxValue::Int(rhs)) => Some(Numbers::Ints(*lhs, *rhs)),
+        (LoxValue::Decimal(_), LoxValue::Number(_))
+        | (LoxValue::Number(_), LoxValue::Decimal(_)) => {
+            return Err(RloxError::RuntimeError(
+                "Can't mix decimals and floats, convert one with decimal() or float().".to_owned(),
+            ));
+        }
+        (LoxValue::Number(_), _) | (_, LoxValue::Number(_)) => lhs
+            .to_f64()
+            .zip(rhs.to_f64())
+            .map(|(lhs, rhs)| Numbers::Floats(lhs, rhs)),
+        (LoxValue::Decimal(_), _) | (_, LoxValue::Decimal(_)) => lhs
+            .to_decimal()
+            .zip(rhs.to_decimal())
+            .map(|(lhs, rhs)| Numbers::Decimals(lhs, rhs)),
+        _ => lhs
+            .to_bigint()
+            .zip(rhs.to_bigint())
+            .map(|(lhs, rhs)| Numbers::BigInts(lhs, rhs)),
+    };
+    Ok(numbers)
+}
+
+/// Whether a float is paired with an int, big integer or decimal.
+fn is_float_mix(lhs: &LoxValue, rhs: &LoxValue) -> bool {
+    matches!(
+        (lhs, rhs),
+        (
+            LoxValue::Number(_),
+            LoxValue::Int(_) | LoxValue::BigInt(_) | LoxValue::Decimal(_)
+        ) | (
+            LoxValue::Int(_) | LoxValue::BigInt(_) | LoxValue::Decimal(_),
+            LoxValue::Number(_)
+        )
+    )
+}
+
+/// Apply an arithmetic or comparison operator. Int results are checked, and
+/// `/` divides ints and big integers as floats.
+fn arithmetic(operator: &Token, numbers: Numbers) -> Result<LoxValue, RloxError> {
+    use Numbers::{BigInts, Decimals, Floats, Ints};
+
+    let int = |value: Option<i64>| value.map(LoxValue::Int).ok_or_else(|| overflow(operator));
+    match (operator.token_type, numbers) {
+        (TokenType::Plus | TokenType::PlusEqual, Ints(lhs, rhs)) => int(lhs.checked_add(rhs)),
+        (TokenType::Plus | TokenType::PlusEqual, Floats(lhs, rhs)) => {
+            Ok(LoxValue::Number(lhs + rhs))
+        }
+        (TokenType::Minus | TokenType::MinusEqual, Ints(lhs, rhs)) => int(lhs.checked_sub(rhs)),
+        (TokenType::Minus | TokenType::MinusEqual, Floats(lhs, rhs)) => {
+            Ok(LoxValue::Number(lhs - rhs))
+        }
+        (TokenType::Star | TokenType::StarEqual, Ints(lhs, rhs)) => int(lhs.checked_mul(rhs)),
+        (TokenType::Star | TokenType::StarEqual, Floats(lhs, rhs)) => {
+            Ok(LoxValue::Number(lhs * rhs))
+        }
+        (TokenType::Slash | TokenType::SlashEqual, Ints(_, 0) | Floats(_, 0.0)) => Err(
+            RloxError::RuntimeError("can not divided by zero".to_owned()),
+        ),
+        (TokenType::Slash | TokenType::SlashEqual, Ints(lhs, rhs)) => {
+            Ok(LoxValue::Number(lhs as f64 / rhs as f64))
+        }
+        (TokenType::Slash | TokenType::SlashEqual, Floats(lhs, rhs)) => {
+            Ok(LoxValue::Number(lhs / rhs))
+        }
+        // `%` and `~/` round the quotient down, so that `a == (a ~/ b) * b + a % b`
+        // and a remainder takes the sign of the divisor.
+        (TokenType::Percent, Ints(_, 0) | Floats(_, 0.0)) => {
+            Err(RloxError::RuntimeError("Modulo by zero.".to_owned()))
+        }
+        (TokenType::Percent, Ints(lhs, rhs)) => {
+            let remainder = lhs.wrapping_rem(rhs);
+            if remainder != 0 && (remainder < 0) != (rhs < 0) {
+                Ok(LoxValue::Int(remainder + rhs))
+            } else {
+                Ok(LoxValue::Int(remainder))
+            }
+        }
+        (TokenType::Percent, Floats(lhs, rhs)) => {
+            Ok(LoxValue::Number(lhs - rhs * (lhs / rhs).floor()))
+        }
+        (TokenType::TildeSlash, Ints(_, 0) | Floats(_, 0.0)) => Err(RloxError::RuntimeError(
+            "Integer division by zero.".to_owned(),
+        )),
+        (TokenType::TildeSlash, Ints(lhs, rhs)) => {
+            let quotient = lhs.checked_div(rhs).ok_or_else(|| overflow(operator))?;
+            if quotient * rhs != lhs && (lhs < 0) != (rhs < 0) {
+                Ok(LoxValue::Int(quotient - 1))
+            } else {
+                Ok(LoxValue::Int(quotient))
+            }
+        }
+        (TokenType::TildeSlash, Floats(lhs, rhs)) => Ok(LoxValue::Number((lhs / rhs).floor())),
+        // A negative exponent gives a fraction.
+        (TokenType::StarStar, Ints(lhs, rhs)) if rhs >= 0 => {
+            int(u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_pow(rhs)))
+        }
+        (TokenType::StarStar, Ints(lhs, rhs)) => {
+            Ok(LoxValue::Number((lhs as f64).powf(rhs as f64)))
+        }
+        (TokenType::StarStar, Floats(lhs, rhs)) => Ok(LoxValue::Number(lhs.powf(rhs))),
+        (TokenType::Greater, Ints(lhs, rhs)) => Ok(LoxValue::Bool(lhs > rhs)),
+        (TokenType::Greater, Floats(lhs, rhs)) => Ok(LoxValue::Bool(lhs > rhs)),
+        (TokenType::GreaterEqual, Ints(lhs, rhs)) => Ok(LoxValue::Bool(lhs >= rhs)),
+        (TokenType::GreaterEqual, Floats(lhs, rhs)) => Ok(LoxValue::Bool(lhs >= rhs)),
+        (TokenType::Less, Ints(lhs, rhs)) => Ok(LoxValue::Bool(lhs < rhs)),
+        (TokenType::Less, Floats(lhs, rhs)) => Ok(LoxValue::Bool(lhs < rhs)),
+        (TokenType::LessEqual, Ints(lhs, rhs)) => Ok(LoxValue::Bool(lhs <= rhs)),
+        (TokenType::LessEqual, Floats(lhs, rhs)) => Ok(LoxValue::Bool(lhs <= rhs)),
+        (TokenType::DotDot, Ints(start, end)) => Ok(LoxValue::IntRange(start, end)),
+        (TokenType::DotDot, Floats(start, end)) => Ok(LoxValue::Range(start, end)),
+        (operator_type, BigInts(lhs, rhs)) => big_arithmetic(operator, operator_type, lhs, rhs),
+        (operator_type, Decimals(lhs, rhs)) => {
+            decimal_arithmetic(operator, operator_type, lhs, rhs)
+        }
+        _ => Err(RloxError::RuntimeError(format!(
+            "Unsupported binary operator '{}'.",
+            operator.lexeme
+        ))),
+    }
+}
+
+fn big_arithmetic(
+    operator: &Token,
+    operator_type: TokenType,
+    lhs: BigInt,
+    rhs: BigInt,
+) -> Result<LoxValue, RloxError> {
+    match operator_type {
+        TokenType::Plus | TokenType::PlusEqual => Ok(LoxValue::BigInt(&lhs + &rhs)),
+        TokenType::Minus | TokenType::MinusEqual => Ok(LoxValue::BigInt(&lhs - &rhs)),
+        TokenType::Star | TokenType::StarEqual => Ok(LoxValue::BigInt(&lhs * &rhs)),
+        TokenType::Slash | TokenType::SlashEqual if rhs.is_zero() => Err(RloxError::RuntimeError(
+            "can not divided by zero".to_owned(),
+        )),
+        TokenType::Slash | TokenType::SlashEqual => {
+            Ok(LoxValue::Number(lhs.to_f64() / rhs.to_f64()))
+        }
+        TokenType::Percent => match lhs.div_rem_floor(&rhs) {
+            Some((_, remainder)) => Ok(LoxValue::BigInt(remainder)),
+            None => Err(RloxError::RuntimeError("Modulo by zero.".to_owned())),
+        },
+        TokenType::TildeSlash => match lhs.div_rem_floor(&rhs) {
+            Some((quotient, _)) => Ok(LoxValue::BigInt(quotient)),
+            None => Err(RloxError::RuntimeError(
+                "Integer division by zero.".to_owned(),
+            )),
+        },
+        TokenType::StarStar if rhs.is_negative() => {
+            Ok(LoxValue::Number(lhs.to_f64().powf(rhs.to_f64())))
+        }
+        TokenType::StarStar => rhs
+            .to_i64()
+            .and_then(|rhs| u32::try_from(rhs).ok())
+            .and_then(|rhs| lhs.pow(rhs))
+            .map(LoxValue::BigInt)
+            .ok_or_else(|| too_large(operator)),
+        TokenType::DotDot => Err(not_range_bounds()),
+        _ => Ok(compare(operator_type, lhs.cmp(&rhs))),
+    }
+}
+
+fn decimal_arithmetic(
+    operator: &Token,
+    operator_type: TokenType,
+    lhs: Decimal,
+    rhs: Decimal,
+) -> Result<LoxValue, RloxError> {
+    match operator_type {
+        TokenType::Plus | TokenType::PlusEqual => Ok(LoxValue::Decimal(&lhs + &rhs)),
+        TokenType::Minus | TokenType::MinusEqual => Ok(LoxValue::Decimal(&lhs - &rhs)),
+        TokenType::Star | TokenType::StarEqual => Ok(LoxValue::Decimal(&lhs * &rhs)),
+        TokenType::Slash | TokenType::SlashEqual => match lhs.div(&rhs) {
+            Some(quotient) => Ok(LoxValue::Decimal(quotient)),
+            None => Err(RloxError::RuntimeError(
+                "can not divided by zero".to_owned(),
+            )),
+        },
+        TokenType::Percent => match lhs.div_rem_floor(&rhs) {
+            Some((_, remainder)) => Ok(LoxValue::Decimal(remainder)),
+            None => Err(RloxError::RuntimeError("Modulo by zero.".to_owned())),
+        },
+        TokenType::TildeSlash => match lhs.div_rem_floor(&rhs) {
+            Some((quotient, _)) => Ok(LoxValue::Decimal(quotient)),
+            None => Err(RloxError::RuntimeError(
+                "Integer division by zero.".to_owned(),
+            )),
+        },
+        // A fractional or negative exponent can't give an exact decimal.
+        TokenType::StarStar if !rhs.is_integer() || rhs < Decimal::from(0) => {
+            Err(RloxError::RuntimeError(
+                "Exponent of a decimal must be a non negative integer.".to_owned(),
+            ))
+        }
+        TokenType::StarStar => rhs
+            .trunc()
+            .to_i64()
+            .and_then(|rhs| u32::try_from(rhs).ok())
+            .and_then(|rhs| lhs.pow(rhs))
+            .map(LoxValue::Decimal)
+            .ok_or_else(|| too_large(operator)),
+        TokenType::DotDot => Err(not_range_bounds()),
+        _ => Ok(compare(operator_type, lhs.cmp(&rhs))),
+    }
+}
+
+/// Result of a comparison operator given how its operands compare.
+fn compare(operator_type: TokenType, ordering: std::cmp::Ordering) -> LoxValue {
+    LoxValue::Bool(match operator_type {
+        TokenType::Greater => ordering.is_gt(),
+        TokenType::GreaterEqual => ordering.is_ge(),
+        TokenType::Less => ordering.is_lt(),
+        _ => ordering.is_le(),
+    })
+}
+
+/// Function stored in a map under `key`, if any.
+fn function_entry(map: &Map, key: &str) -> Option<LoxValue> {
+    match map.get(&MapKey::String(key.to_owned())) {
+        Some(function @ (LoxValue::Function(_) | LoxValue::NativeFunction(_))) => {
+            Some(function.clone())
+        }
+        _ => None,
+    }
+}
+
+fn not_range_bounds() -> RloxError {
+    RloxError::RuntimeError("Range bounds must be ints or floats.".to_owned())
+}
+
+fn too_large(operator: &Token) -> RloxError {
+    RloxError::RuntimeError(format!("Result of '{}' is too large.", operator.lexeme))
+}
+
+fn overflow(operator: &Token) -> RloxError {
+    RloxError::RuntimeError(format!("Integer overflow in '{}'.", operator.lexeme))
+}
+
+/// Check that a value is an int, or an integral float or big integer within 64
+/// bits, as bitwise operators need.
+fn integer(value: &LoxValue, operator: &Token) -> Result<i64, RloxError> {
+    match value {
+        LoxValue::Int(n) => Ok(*n),
+        LoxValue::BigInt(n) if let Some(n) = n.to_i64() => Ok(n),
+        LoxValue::Number(n) if let Some(n) = float_to_int(*n) => Ok(n),
+        _ => Err(RloxError::RuntimeError(format!(
+            "Operands of '{}' must be 64-bit integers, got {}.",
+            operator.lexeme,
+            value.repr()
+        ))),
+    }
+}
+
+/// Format an arity range for error messages.
+fn arity_to_string(arity: &RangeInclusive<usize>) -> String {
+    if arity.start() == arity.end() {
+        arity.start().to_string()
+    } else {
+        format!("{} to {}", arity.start(), arity.end())
     }
 }
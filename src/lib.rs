@@ -1,9 +1,22 @@
 pub mod ast;
+pub mod bignum;
+pub mod coverage;
+#[cfg(feature = "dap")]
+pub mod dap;
+pub mod debugger;
 pub mod environment;
 pub mod error;
 pub mod interpreter;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod native;
 pub mod parser;
+pub mod profile;
+#[cfg(any(feature = "lsp", feature = "dap"))]
+pub mod rpc;
 pub mod runner;
 pub mod scanner;
+pub mod test_runner;
 pub mod token;
+pub mod trace;
 pub mod value;
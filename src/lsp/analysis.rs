@@ -0,0 +1,384 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    ast::{
+        expr::{self, Expr},
+        stmt::{self, Stmt},
+    },
+    error::RloxError,
+    parser::Parser,
+    scanner::Scanner,
+    token::{LiteralType, Span, Token},
+};
+
+/// A problem found while scanning or parsing a document.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    /// 1-based line of the problem.
+    pub line: usize,
+    pub message: String,
+}
+
+/// A variable declared through `var` or as a parameter.
+#[derive(Debug, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    /// Span of the declared name.
+    pub span: Span,
+    pub line: usize,
+    /// Source line of the declaration, shown on hover.
+    pub declaration: String,
+}
+
+/// A use of a declared variable.
+#[derive(Debug, PartialEq)]
+pub struct Reference {
+    pub span: Span,
+    /// Index of the referenced symbol.
+    pub symbol: usize,
+}
+
+/// Everything the language server knows about a document.
+#[derive(Debug, Default)]
+pub struct Analysis {
+    pub diagnostics: Vec<Diagnostic>,
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+impl Analysis {
+    /// Scan, parse and resolve variables of a lox source.
+    pub fn new(source: &str) -> Self {
+        let mut analysis = Analysis::default();
+        let mut scanner = Scanner::new(source.to_owned());
+        let tokens = scanner.scan_tokens();
+        analysis.add_diagnostics(&scanner.errors);
+        let Ok(tokens) = tokens else {
+            return analysis;
+        };
+
+        let mut parser = Parser::new(tokens);
+        let program = parser.parse();
+        analysis.add_diagnostics(&parser.errors);
+        if let Some(program) = program {
+            let mut resolver = Resolver::new(source);
+            program.accept(&mut resolver);
+            analysis.symbols = resolver.symbols;
+            analysis.references = resolver.references;
+        }
+
+        analysis
+    }
+
+    fn add_diagnostics(&mut self, errors: &[RloxError]) {
+        self.diagnostics
+            .extend(errors.iter().map(|error| Diagnostic {
+                line: match error {
+                    RloxError::LexicalError(line, ..) | RloxError::SyntaxError(line, ..) => *line,
+                    _ => 1,
+                },
+                message: error.to_string(),
+            }));
+    }
+
+    /// Return the symbol declared or referenced at the given byte offset.
+    pub fn symbol_at(&self, offset: usize) -> Option<usize> {
+        let contains = |span: &Span| span.start <= offset && offset <= span.end;
+        self.symbols
+            .iter()
+            .position(|symbol| contains(&symbol.span))
+            .or_else(|| {
+                self.references
+                    .iter()
+                    .find(|reference| contains(&reference.span))
+                    .map(|reference| reference.symbol)
+            })
+    }
+
+    /// Return spans of all references to a symbol.
+    pub fn references_to(&self, symbol: usize) -> Vec<Span> {
+        self.references
+            .iter()
+            .filter(|reference| reference.symbol == symbol)
+            .map(|reference| reference.span)
+            .collect()
+    }
+}
+
+/// Converts byte offsets to LSP positions (0-based line, UTF-16 column) and back.
+#[derive(Debug)]
+pub struct LineIndex {
+    /// Byte offset where each line starts.
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Self { starts }
+    }
+
+    pub fn position(&self, source: &str, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.starts[line];
+        let column = source[start..offset.min(source.len())]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        (line, column)
+    }
+
+    pub fn offset(&self, source: &str, line: usize, column: usize) -> usize {
+        let Some(&start) = self.starts.get(line) else {
+            return source.len();
+        };
+        let mut units = 0;
+        for (i, c) in source[start..].char_indices() {
+            if units >= column || c == '\n' {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        source.len()
+    }
+
+    /// Byte range of a 1-based line, without the line break.
+    pub fn line_span(&self, source: &str, line: usize) -> Span {
+        let start = self
+            .starts
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or(source.len());
+        let end = self
+            .starts
+            .get(line)
+            .map_or(source.len(), |&next| next - 1)
+            .max(start);
+        Span { start, end }
+    }
+}
+
+/// Walk the AST binding each variable use to its `var` declaration.
+struct Resolver<'a> {
+    source: &'a str,
+    line_index: LineIndex,
+    /// Innermost scope last, mapping names to symbol index.
+    scopes: Vec<HashMap<String, usize>>,
+    symbols: Vec<Symbol>,
+    references: Vec<Reference>,
+    /// First declaration of each global.
+    globals: HashMap<String, usize>,
+    /// Uses which may refer to a later global declaration.
+    unresolved: Vec<Token>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            line_index: LineIndex::new(source),
+            scopes: vec![HashMap::new()],
+            symbols: vec![],
+            references: vec![],
+            globals: HashMap::new(),
+            unresolved: vec![],
+        }
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let span = self.line_index.line_span(self.source, name.line);
+        self.symbols.push(Symbol {
+            name: name.lexeme.clone(),
+            span: name.span,
+            line: name.line,
+            declaration: self.source[span.start..span.end].trim().to_owned(),
+        });
+        let index = self.symbols.len() - 1;
+        if self.scopes.len() == 1 {
+            self.globals.entry(name.lexeme.clone()).or_insert(index);
+        }
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.lexeme.clone(), index);
+    }
+
+    fn resolve(&mut self, name: &Token) {
+        let symbol = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.lexeme).copied());
+        match symbol {
+            Some(symbol) => self.references.push(Reference {
+                span: name.span,
+                symbol,
+            }),
+            None => self.unresolved.push(name.clone()),
+        }
+    }
+
+    /// Bind remaining uses to the first global declaration of the same name,
+    /// since globals are looked up at runtime.
+    fn resolve_globals(&mut self) {
+        for name in std::mem::take(&mut self.unresolved) {
+            if let Some(&symbol) = self.globals.get(&name.lexeme) {
+                self.references.push(Reference {
+                    span: name.span,
+                    symbol,
+                });
+            }
+        }
+        self.references
+            .sort_by_key(|reference| reference.span.start);
+    }
+}
+
+impl expr::Visitor<()> for Resolver<'_> {
+    fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) {
+        value.accept(self);
+        self.resolve(name);
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, _operator: &Token, right: &Expr) {
+        left.accept(self);
+        right.accept(self);
+    }
+
+    fn visit_call(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) {
+        callee.accept(self);
+        for argument in arguments {
+            argument.accept(self);
+        }
+    }
+
+    fn visit_grouping(&mut self, expression: &Expr) {
+        expression.accept(self);
+    }
+
+    fn visit_literal(&mut self, _value: &LiteralType) {}
+
+    fn visit_unary(&mut self, _operator: &Token, right: &Expr) {
+        right.accept(self);
+    }
+
+    fn visit_ternary(&mut self, condition: &Expr, truepart: &Expr, falsepart: &Expr) {
+        condition.accept(self);
+        truepart.accept(self);
+        falsepart.accept(self);
+    }
+
+    fn visit_variable(&mut self, name: &Token) {
+        self.resolve(name);
+    }
+
+    fn visit_list(&mut self, _bracket: &Token, elements: &[Expr]) {
+        for element in elements {
+            element.accept(self);
+        }
+    }
+
+    fn visit_map(&mut self, _brace: &Token, entries: &[(Expr, Expr)]) {
+        for (key, value) in entries {
+            key.accept(self);
+            value.accept(self);
+        }
+    }
+
+    fn visit_index(&mut self, object: &Expr, _bracket: &Token, index: &Expr) {
+        object.accept(self);
+        index.accept(self);
+    }
+
+    fn visit_index_set(&mut self, object: &Expr, _bracket: &Token, index: &Expr, value: &Expr) {
+        object.accept(self);
+        index.accept(self);
+        value.accept(self);
+    }
+
+    fn visit_compound_assignment(&mut self, target: &Expr, _operator: &Token, value: &Expr) {
+        target.accept(self);
+        value.accept(self);
+    }
+
+    fn visit_update(&mut self, _operator: &Token, target: &Expr, _prefix: bool) {
+        target.accept(self);
+    }
+
+    fn visit_lambda(&mut self, _keyword: &Token, params: &[Token], body: &Rc<Stmt>) {
+        self.scopes.push(HashMap::new());
+        for param in params {
+            self.declare(param);
+        }
+        body.accept(self);
+        self.scopes.pop();
+    }
+}
+
+impl stmt::Visitor<()> for Resolver<'_> {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) {
+        self.scopes.push(HashMap::new());
+        for stmt in statements {
+            stmt.accept(self);
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_program_stmt(&mut self, declarations: &[Stmt]) {
+        for stmt in declarations {
+            stmt.accept(self);
+        }
+        self.resolve_globals();
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: &Option<Expr>) {
+        if let Some(expr) = initializer {
+            expr.accept(self);
+        }
+        self.declare(name);
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) {
+        expression.accept(self);
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) {
+        expression.accept(self);
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) {
+        condition.accept(self);
+        body.accept(self);
+    }
+
+    fn visit_for_in_stmt(&mut self, name: &Token, iterable: &Expr, body: &Stmt) {
+        iterable.accept(self);
+        self.scopes.push(HashMap::new());
+        self.declare(name);
+        body.accept(self);
+        self.scopes.pop();
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) {
+        condition.accept(self);
+        then_branch.accept(self);
+        if let Some(else_branch) = else_branch {
+            else_branch.accept(self);
+        }
+    }
+
+    fn visit_break_stmt(&mut self, _keyword: &Token) {}
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: &Option<Expr>) {
+        if let Some(expr) = value {
+            expr.accept(self);
+        }
+    }
+
+    fn visit_continue_stmt(&mut self, _keyword: &Token) {}
+
+    fn visit_test_stmt(&mut self, _name: &Token, body: &Stmt) {
+        body.accept(self);
+    }
+}
@@ -0,0 +1,15 @@
+//! Language server for lox, speaking LSP over stdio.
+
+pub mod analysis;
+pub mod server;
+
+use std::io;
+
+use crate::{error::RloxError, lsp::server::Server};
+
+/// Serve LSP requests from stdin until the client exits.
+pub fn run_stdio() -> Result<(), RloxError> {
+    let stdin = io::stdin();
+    Server::new(io::stdout()).run(stdin.lock())?;
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    lsp::analysis::{Analysis, LineIndex},
+    rpc::{read_message, write_message},
+    token::Span,
+};
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// LSP `SymbolKind::Variable`.
+const SYMBOL_KIND_VARIABLE: u64 = 13;
+/// LSP `DiagnosticSeverity::Error`.
+const SEVERITY_ERROR: u64 = 1;
+
+/// An open text document.
+struct Document {
+    text: String,
+    line_index: LineIndex,
+    analysis: Analysis,
+}
+
+impl Document {
+    fn new(text: String) -> Self {
+        Self {
+            line_index: LineIndex::new(&text),
+            analysis: Analysis::new(&text),
+            text,
+        }
+    }
+
+    fn range(&self, span: Span) -> Value {
+        let (start_line, start_column) = self.line_index.position(&self.text, span.start);
+        let (end_line, end_column) = self.line_index.position(&self.text, span.end);
+        json!({
+            "start": { "line": start_line, "character": start_column },
+            "end": { "line": end_line, "character": end_column },
+        })
+    }
+
+    /// Return the symbol at a LSP position.
+    fn symbol_at(&self, position: &Value) -> Option<usize> {
+        let line = position["line"].as_u64()? as usize;
+        let column = position["character"].as_u64()? as usize;
+        let offset = self.line_index.offset(&self.text, line, column);
+        self.analysis.symbol_at(offset)
+    }
+}
+
+/// Language server speaking LSP over a pair of streams.
+pub struct Server<W: Write> {
+    writer: W,
+    documents: HashMap<String, Document>,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Serve requests until `exit` notification or end of input.
+    pub fn run<R: BufRead>(&mut self, mut reader: R) -> io::Result<()> {
+        while let Some(message) = read_message(&mut reader)? {
+            if message["method"] == "exit" {
+                break;
+            }
+            self.handle(message)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, message: Value) -> io::Result<()> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let Some(id) = message.get("id") else {
+            return self.notification(method, params);
+        };
+
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": { "name": "rlox", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => Value::Null,
+            "textDocument/definition" => self.definition(params),
+            "textDocument/references" => self.references(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/documentSymbol" => self.document_symbol(params),
+            _ => {
+                return write_message(
+                    &mut self.writer,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": METHOD_NOT_FOUND,
+                            "message": format!("Unknown method: {method}."),
+                        },
+                    }),
+                );
+            }
+        };
+        write_message(
+            &mut self.writer,
+            &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        )
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> io::Result<()> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents
+                    .insert(uri.clone(), Document::new(text.to_owned()));
+                self.publish_diagnostics(&uri)
+            }
+            "textDocument/didChange" => {
+                // Only full document sync is advertised, so the last change holds the whole text.
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    self.documents
+                        .insert(uri.clone(), Document::new(text.to_owned()));
+                }
+                self.publish_diagnostics(&uri)
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                self.publish_diagnostics(&uri)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> io::Result<()> {
+        let diagnostics: Vec<Value> = match self.documents.get(uri) {
+            Some(document) => document
+                .analysis
+                .diagnostics
+                .iter()
+                .map(|diagnostic| {
+                    let span = document
+                        .line_index
+                        .line_span(&document.text, diagnostic.line);
+                    json!({
+                        "range": document.range(span),
+                        "severity": SEVERITY_ERROR,
+                        "source": "rlox",
+                        "message": diagnostic.message,
+                    })
+                })
+                .collect(),
+            None => vec![],
+        };
+        write_message(
+            &mut self.writer,
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": diagnostics },
+            }),
+        )
+    }
+
+    /// Return the document and symbol targeted by a position request.
+    fn target<'a>(&'a self, params: &'a Value) -> Option<(&'a str, &'a Document, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let document = self.documents.get(uri)?;
+        let symbol = document.symbol_at(&params["position"])?;
+        Some((uri, document, symbol))
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        match self.target(params) {
+            Some((uri, document, symbol)) => json!({
+                "uri": uri,
+                "range": document.range(document.analysis.symbols[symbol].span),
+            }),
+            None => Value::Null,
+        }
+    }
+
+    fn references(&self, params: &Value) -> Value {
+        let Some((uri, document, symbol)) = self.target(params) else {
+            return Value::Null;
+        };
+        let mut spans = vec![];
+        if params["context"]["includeDeclaration"].as_bool() == Some(true) {
+            spans.push(document.analysis.symbols[symbol].span);
+        }
+        spans.extend(document.analysis.references_to(symbol));
+        spans
+            .into_iter()
+            .map(|span| json!({ "uri": uri, "range": document.range(span) }))
+            .collect()
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        match self.target(params) {
+            Some((_, document, symbol)) => {
+                let symbol = &document.analysis.symbols[symbol];
+                json!({
+                    "contents": {
+                        "kind": "markdown",
+                        "value": format!("```lox\n{}\n```", symbol.declaration),
+                    },
+                })
+            }
+            None => Value::Null,
+        }
+    }
+
+    fn document_symbol(&self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let Some(document) = self.documents.get(uri) else {
+            return Value::Null;
+        };
+        document
+            .analysis
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let line = document.line_index.line_span(&document.text, symbol.line);
+                json!({
+                    "name": symbol.name,
+                    "detail": symbol.declaration,
+                    "kind": SYMBOL_KIND_VARIABLE,
+                    "range": document.range(line),
+                    "selectionRange": document.range(symbol.span),
+                })
+            })
+            .collect()
+    }
+}
@@ -1,19 +1,249 @@
-use std::{cmp::Ordering, env};
+use std::{cell::RefCell, env, fs, fs::File, io, ops::RangeInclusive, rc::Rc};
 
 use rlox::{
+    coverage::Coverage,
     error::RloxError,
-    runner::{run_file, run_prompt},
+    interpreter::Interpreter,
+    profile::{Profile, Profiler},
+    runner::{format_paths, run_file_with, run_prompt},
+    test_runner::{ReportFormat, run_tests},
+    trace::Tracer,
 };
 
+const USAGE: &str = "Usage: rlox [script [arg...]]
+       rlox test [--format=summary|tap|junit] [--coverage] [--lcov=path] [--fail-under=percent] [path...]
+       rlox fmt [--check] [path...]
+       rlox --trace [--output=path] [--lines=start-end] script
+       rlox --profile [--folded=path] script
+       rlox --dump-tokens=json|--dump-ast=json script
+       rlox lsp
+       rlox dap";
+
 fn main() -> Result<(), RloxError> {
     let args: Vec<String> = env::args().collect();
-    match args.len().cmp(&2) {
-        Ordering::Greater => {
-            println!("Usage: rlox [script]");
-            // exit with wrong number of arguments.
-            std::process::exit(64);
+    let result = match args.get(1).map(String::as_str) {
+        Some("test") => test(&args[2..]),
+        Some("fmt") => fmt(&args[2..]),
+        Some("lsp") if args.len() == 2 => lsp(),
+        Some("dap") if args.len() == 2 => dap(),
+        Some("--trace") => trace(&args[2..]),
+        Some("--profile") => profile(&args[2..]),
+        Some(flag) if flag.starts_with("--dump-") && args.len() == 3 => dump(flag, &args[2]),
+        Some(path) if !path.starts_with("--") => run_script(path, &args[2..]),
+        None => run_prompt(),
+        _ => usage(),
+    };
+    match result {
+        Err(RloxError::Exit(status)) => std::process::exit(status),
+        result => result,
+    }
+}
+
+/// Run a script, passing it the remaining command line arguments.
+fn run_script(path: &str, args: &[String]) -> Result<(), RloxError> {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_args(args.to_vec());
+    run_file_with(path, &mut interpreter)
+}
+
+/// Run `rlox test` subcommand.
+///
+/// `--lcov` and `--fail-under` imply `--coverage`, which prints a coverage summary
+/// after the test report.
+fn test(args: &[String]) -> Result<(), RloxError> {
+    let mut format = ReportFormat::Summary;
+    let mut coverage = false;
+    let mut lcov = None;
+    let mut fail_under = None;
+    let mut paths = vec![];
+    for arg in args {
+        if let Some(value) = arg.strip_prefix("--format=") {
+            format = match value.parse() {
+                Ok(format) => format,
+                Err(message) => {
+                    eprintln!("{message}");
+                    usage()
+                }
+            };
+        } else if arg == "--coverage" {
+            coverage = true;
+        } else if let Some(path) = arg.strip_prefix("--lcov=") {
+            coverage = true;
+            lcov = Some(path);
+        } else if let Some(value) = arg.strip_prefix("--fail-under=") {
+            coverage = true;
+            fail_under = match value.parse::<f64>() {
+                Ok(percent) => Some(percent),
+                Err(_) => {
+                    eprintln!("Invalid coverage percentage: {value}.");
+                    usage()
+                }
+            };
+        } else if arg.starts_with("--") {
+            usage()
+        } else {
+            paths.push(arg.clone());
         }
-        Ordering::Equal => run_file(&args[1]),
-        Ordering::Less => run_prompt(),
     }
+    if paths.is_empty() {
+        paths.push(".".to_owned());
+    }
+
+    let mut collected = Coverage::default();
+    let passed = run_tests(&paths, format, coverage.then_some(&mut collected))?;
+    if coverage {
+        print!("{}", collected.summary());
+    }
+    if let Some(path) = lcov {
+        fs::write(path, collected.lcov())?;
+    }
+    let covered = fail_under.is_none_or(|minimum| {
+        let percent = collected.line_percent();
+        if percent < minimum {
+            eprintln!("Line coverage {percent:.1}% is below {minimum}%.");
+        }
+        percent >= minimum
+    });
+
+    if !passed || !covered {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Run `rlox fmt` subcommand.
+fn fmt(args: &[String]) -> Result<(), RloxError> {
+    let mut check = false;
+    let mut paths = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ if arg.starts_with("--") => usage(),
+            _ => paths.push(arg.clone()),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(".".to_owned());
+    }
+
+    if !format_paths(&paths, check)? && check {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Run a script logging each statement and expression, to stderr unless `--output` is given.
+fn trace(args: &[String]) -> Result<(), RloxError> {
+    let mut output: Box<dyn io::Write> = Box::new(io::stderr());
+    let mut lines = None;
+    let mut script = None;
+    for arg in args {
+        if let Some(path) = arg.strip_prefix("--output=") {
+            output = Box::new(File::create(path)?);
+        } else if let Some(range) = arg.strip_prefix("--lines=") {
+            match parse_lines(range) {
+                Some(range) => lines = Some(range),
+                None => {
+                    eprintln!("Invalid line range: {range}.");
+                    usage()
+                }
+            }
+        } else if arg.starts_with("--") || script.is_some() {
+            usage()
+        } else {
+            script = Some(arg);
+        }
+    }
+    let Some(script) = script else { usage() };
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_hook(Box::new(Tracer::new(output, lines)));
+    run_file_with(script, &mut interpreter)
+}
+
+/// Parse a line range such as `3-10`, or a single line such as `7`.
+fn parse_lines(range: &str) -> Option<RangeInclusive<usize>> {
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    Some(start.parse().ok()?..=end.parse().ok()?)
+}
+
+/// Run a script then print a hot spot report to stderr, and with `--folded`
+/// write stacks for flamegraph tools.
+fn profile(args: &[String]) -> Result<(), RloxError> {
+    let mut folded = None;
+    let mut script = None;
+    for arg in args {
+        if let Some(path) = arg.strip_prefix("--folded=") {
+            folded = Some(path);
+        } else if arg.starts_with("--") || script.is_some() {
+            usage()
+        } else {
+            script = Some(arg);
+        }
+    }
+    let Some(script) = script else { usage() };
+
+    let profile = Rc::new(RefCell::new(Profile::default()));
+    let mut interpreter = Interpreter::new();
+    interpreter.set_hook(Box::new(Profiler::new(Rc::clone(&profile))));
+    // Report even when the script calls `exit()`.
+    let result = run_file_with(script, &mut interpreter);
+
+    let profile = profile.borrow();
+    eprint!("{}", profile.report());
+    if let Some(path) = folded {
+        fs::write(path, profile.folded())?;
+    }
+    result
+}
+
+/// Dump tokens or AST of a script, e.g. `rlox --dump-ast=json script`.
+#[cfg(feature = "json")]
+fn dump(flag: &str, path: &str) -> Result<(), RloxError> {
+    use rlox::runner::{dump_ast_json, dump_tokens_json};
+
+    let content = std::fs::read_to_string(path)?;
+    let output = match flag {
+        "--dump-tokens=json" => dump_tokens_json(&content)?,
+        "--dump-ast=json" => dump_ast_json(&content)?,
+        _ => usage(),
+    };
+    println!("{output}");
+    Ok(())
+}
+
+#[cfg(not(feature = "json"))]
+fn dump(_flag: &str, _path: &str) -> Result<(), RloxError> {
+    eprintln!("rlox was built without the `json` feature.");
+    std::process::exit(64);
+}
+
+/// Run `rlox lsp` subcommand.
+#[cfg(feature = "lsp")]
+fn lsp() -> Result<(), RloxError> {
+    rlox::lsp::run_stdio()
+}
+
+#[cfg(not(feature = "lsp"))]
+fn lsp() -> Result<(), RloxError> {
+    eprintln!("rlox was built without the `lsp` feature.");
+    std::process::exit(64);
+}
+
+/// Run `rlox dap` subcommand.
+#[cfg(feature = "dap")]
+fn dap() -> Result<(), RloxError> {
+    rlox::dap::run_stdio()
+}
+
+#[cfg(not(feature = "dap"))]
+fn dap() -> Result<(), RloxError> {
+    eprintln!("rlox was built without the `dap` feature.");
+    std::process::exit(64);
+}
+
+fn usage() -> ! {
+    println!("{USAGE}");
+    // exit with wrong number of arguments.
+    std::process::exit(64);
 }
@@ -0,0 +1,154 @@
+use super::{NativeFunction, number_arg, type_error};
+use crate::{
+    bignum::Decimal,
+    error::RloxError,
+    interpreter::Interpreter,
+    value::{LoxValue, float_to_int},
+};
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "str",
+            arity: 1..=1,
+            capability: None,
+            function: str,
+        },
+        NativeFunction {
+            name: "num",
+            arity: 1..=1,
+            capability: None,
+            function: num,
+        },
+        NativeFunction {
+            name: "int",
+            arity: 1..=1,
+            capability: None,
+            function: int,
+        },
+        NativeFunction {
+            name: "float",
+            arity: 1..=1,
+            capability: None,
+            function: float,
+        },
+        NativeFunction {
+            name: "bigint",
+            arity: 1..=1,
+            capability: None,
+            function: bigint,
+        },
+        NativeFunction {
+            name: "decimal",
+            arity: 1..=1,
+            capability: None,
+            function: decimal,
+        },
+        NativeFunction {
+            name: "type",
+            arity: 1..=1,
+            capability: None,
+            function: type_of,
+        },
+    ]
+}
+
+/// `str(v)`: `v` as printed by `print`.
+fn str(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    match &arguments[0] {
+        LoxValue::String(s) => Ok(LoxValue::String(s.clone())),
+        value => interpreter.new_string(value.to_string()),
+    }
+}
+
+/// `num(v)`: number written in a string, nil if it isn't one. Integers are read
+/// as ints, other numbers as floats.
+fn num(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    match &arguments[0] {
+        value @ (LoxValue::Int(_)
+        | LoxValue::BigInt(_)
+        | LoxValue::Decimal(_)
+        | LoxValue::Number(_)) => Ok(value.clone()),
+        LoxValue::String(s) => Ok(parse_number(s).unwrap_or(LoxValue::Nil)),
+        value => Err(type_error("num", 0, "string or number", value)),
+    }
+}
+
+/// Parse a decimal number, rejecting spellings such as `inf` or `NaN`.
+fn parse_number(s: &str) -> Option<LoxValue> {
+    let s = s.trim();
+    let decimal = s
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'));
+    if !decimal {
+        return None;
+    }
+    match s.parse() {
+        Ok(n) => Some(LoxValue::Int(n)),
+        Err(_) => s.parse().ok().map(LoxValue::Number),
+    }
+}
+
+/// `int(x)`: number `x` truncated toward zero to an int.
+fn int(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let n = match &arguments[0] {
+        LoxValue::Int(n) => Some(*n),
+        LoxValue::BigInt(n) => n.to_i64(),
+        LoxValue::Decimal(n) => n.trunc().to_i64(),
+        _ => float_to_int(number_arg("int", arguments, 0)?.trunc()),
+    };
+    match n {
+        Some(n) => Ok(LoxValue::Int(n)),
+        None => Err(RloxError::RuntimeError(format!(
+            "int() can't convert {} to an int.",
+            arguments[0]
+        ))),
+    }
+}
+
+/// `float(x)`: number `x` as a float.
+fn float(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    Ok(LoxValue::Number(number_arg("float", arguments, 0)?))
+}
+
+/// `bigint(v)`: number `v` truncated toward zero, or the integer written in a
+/// string, as a big integer. Nil if the string isn't an integer.
+fn bigint(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    match &arguments[0] {
+        LoxValue::String(s) => Ok(s.trim().parse().map_or(LoxValue::Nil, LoxValue::BigInt)),
+        LoxValue::Decimal(n) => Ok(LoxValue::BigInt(n.trunc())),
+        LoxValue::Number(n) => match Decimal::from_f64(*n) {
+            Some(n) => Ok(LoxValue::BigInt(n.trunc())),
+            None => Err(not_finite("bigint", *n)),
+        },
+        value => value
+            .to_bigint()
+            .map(LoxValue::BigInt)
+            .ok_or_else(|| type_error("bigint", 0, "string or number", value)),
+    }
+}
+
+/// `decimal(v)`: number `v`, or the number written in a string, as a decimal.
+/// Floats convert as printed, so `decimal(0.1)` is `0.1`. Nil if the string
+/// isn't a decimal number.
+fn decimal(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    match &arguments[0] {
+        LoxValue::String(s) => Ok(s.trim().parse().map_or(LoxValue::Nil, LoxValue::Decimal)),
+        LoxValue::Number(n) => Decimal::from_f64(*n)
+            .map(LoxValue::Decimal)
+            .ok_or_else(|| not_finite("decimal", *n)),
+        value => value
+            .to_decimal()
+            .map(LoxValue::Decimal)
+            .ok_or_else(|| type_error("decimal", 0, "string or number", value)),
+    }
+}
+
+fn not_finite(name: &str, n: f64) -> RloxError {
+    RloxError::RuntimeError(format!("{name}() can't convert {n} to a {name}."))
+}
+
+/// `type(v)`: name of the type of `v`.
+fn type_of(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    interpreter.new_string(arguments[0].type_name().to_owned())
+}
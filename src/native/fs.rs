@@ -0,0 +1,76 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use super::{Capability, NativeFunction, string_arg};
+use crate::{error::RloxError, interpreter::Interpreter, value::LoxValue};
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "readFile",
+            arity: 1..=1,
+            capability: Some(Capability::Fs),
+            function: read_file,
+        },
+        NativeFunction {
+            name: "writeFile",
+            arity: 2..=2,
+            capability: Some(Capability::Fs),
+            function: write_file,
+        },
+        NativeFunction {
+            name: "appendFile",
+            arity: 2..=2,
+            capability: Some(Capability::Fs),
+            function: append_file,
+        },
+        NativeFunction {
+            name: "fileExists",
+            arity: 1..=1,
+            capability: Some(Capability::Fs),
+            function: file_exists,
+        },
+    ]
+}
+
+/// Runtime error for a failed file operation.
+fn fs_error(name: &str, path: &str, e: io::Error) -> RloxError {
+    RloxError::RuntimeError(format!("{name}() failed on '{path}': {e}."))
+}
+
+/// `readFile(path)`: content of a file.
+fn read_file(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let path = string_arg("readFile", arguments, 0)?;
+    let content = fs::read_to_string(path).map_err(|e| fs_error("readFile", path, e))?;
+    interpreter.new_string(content)
+}
+
+/// `writeFile(path, s)`: replace the content of a file, creating it if needed.
+fn write_file(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let path = string_arg("writeFile", arguments, 0)?;
+    let content = string_arg("writeFile", arguments, 1)?;
+    fs::write(path, content).map_err(|e| fs_error("writeFile", path, e))?;
+    Ok(LoxValue::Nil)
+}
+
+/// `appendFile(path, s)`: add to the end of a file, creating it if needed.
+fn append_file(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let path = string_arg("appendFile", arguments, 0)?;
+    let content = string_arg("appendFile", arguments, 1)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| fs_error("appendFile", path, e))?;
+    Ok(LoxValue::Nil)
+}
+
+/// `fileExists(path)`: whether a file or directory exists at `path`.
+fn file_exists(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let path = string_arg("fileExists", arguments, 0)?;
+    Ok(LoxValue::Bool(Path::new(path).exists()))
+}
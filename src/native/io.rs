@@ -0,0 +1,33 @@
+use super::{Capability, NativeFunction};
+use crate::{error::RloxError, interpreter::Interpreter, value::LoxValue};
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "readLine",
+            arity: 0..=0,
+            capability: Some(Capability::Io),
+            function: read_line,
+        },
+        NativeFunction {
+            name: "eprint",
+            arity: 1..=1,
+            capability: Some(Capability::Io),
+            function: eprint,
+        },
+    ]
+}
+
+/// `readLine()`: next line of input without its line terminator, nil at end of input.
+fn read_line(interpreter: &mut Interpreter, _: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    match interpreter.read_line()? {
+        Some(line) => interpreter.new_string(line),
+        None => Ok(LoxValue::Nil),
+    }
+}
+
+/// `eprint(v)`: print `v` to the error output.
+fn eprint(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    writeln!(interpreter.error_output(), "{}", arguments[0])?;
+    Ok(LoxValue::Nil)
+}
@@ -0,0 +1,75 @@
+use super::{NativeFunction, index_arg, list_arg};
+use crate::{error::RloxError, interpreter::Interpreter, value::LoxValue};
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "push",
+            arity: 2..=2,
+            capability: None,
+            function: push,
+        },
+        NativeFunction {
+            name: "pop",
+            arity: 1..=1,
+            capability: None,
+            function: pop,
+        },
+        NativeFunction {
+            name: "insert",
+            arity: 3..=3,
+            capability: None,
+            function: insert,
+        },
+        NativeFunction {
+            name: "remove",
+            arity: 2..=2,
+            capability: None,
+            function: remove,
+        },
+    ]
+}
+
+/// `push(list, v)`: add `v` at the end of `list`.
+fn push(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let list = list_arg("push", arguments, 0)?;
+    list.borrow_mut().push(arguments[1].clone());
+    Ok(LoxValue::Nil)
+}
+
+/// `pop(list)`: remove and return the last element of `list`.
+fn pop(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let list = list_arg("pop", arguments, 0)?;
+    let value = list.borrow_mut().pop();
+    value.ok_or_else(|| RloxError::RuntimeError("pop() on an empty list.".to_owned()))
+}
+
+/// `insert(list, i, v)`: insert `v` before the element at `i`, or at the end
+/// when `i` is the length of `list`.
+fn insert(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let list = list_arg("insert", arguments, 0)?;
+    let index = index_arg("insert", arguments, 1)?;
+    let mut list = list.borrow_mut();
+    if index > list.len() {
+        return Err(out_of_bounds("insert", index, list.len()));
+    }
+    list.insert(index, arguments[2].clone());
+    Ok(LoxValue::Nil)
+}
+
+/// `remove(list, i)`: remove and return the element at `i`.
+fn remove(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let list = list_arg("remove", arguments, 0)?;
+    let index = index_arg("remove", arguments, 1)?;
+    let mut list = list.borrow_mut();
+    if index >= list.len() {
+        return Err(out_of_bounds("remove", index, list.len()));
+    }
+    Ok(list.remove(index))
+}
+
+fn out_of_bounds(name: &str, index: usize, len: usize) -> RloxError {
+    RloxError::RuntimeError(format!(
+        "{name}() index {index} out of bounds for list of length {len}."
+    ))
+}
@@ -0,0 +1,67 @@
+use super::{NativeFunction, map_arg};
+use crate::{
+    error::RloxError,
+    interpreter::Interpreter,
+    value::{LoxValue, MapKey},
+};
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "keys",
+            arity: 1..=1,
+            capability: None,
+            function: keys,
+        },
+        NativeFunction {
+            name: "values",
+            arity: 1..=1,
+            capability: None,
+            function: values,
+        },
+        NativeFunction {
+            name: "has",
+            arity: 2..=2,
+            capability: None,
+            function: has,
+        },
+        NativeFunction {
+            name: "delete",
+            arity: 2..=2,
+            capability: None,
+            function: delete,
+        },
+    ]
+}
+
+/// `keys(map)`: list of the keys of `map`, in insertion order.
+fn keys(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let map = map_arg("keys", arguments, 0)?;
+    let keys = map.borrow().iter().map(|(key, _)| key.to_value()).collect();
+    Ok(LoxValue::list(keys))
+}
+
+/// `values(map)`: list of the values of `map`, in insertion order.
+fn values(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let map = map_arg("values", arguments, 0)?;
+    let values = map
+        .borrow()
+        .iter()
+        .map(|(_, value)| value.clone())
+        .collect();
+    Ok(LoxValue::list(values))
+}
+
+/// `has(map, key)`: whether `map` has a value for `key`.
+fn has(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let map = map_arg("has", arguments, 0)?;
+    let key = MapKey::try_from(&arguments[1])?;
+    Ok(LoxValue::Bool(map.borrow().get(&key).is_some()))
+}
+
+/// `delete(map, key)`: remove `key` from `map`, returning whether it was present.
+fn delete(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let map = map_arg("delete", arguments, 0)?;
+    let key = MapKey::try_from(&arguments[1])?;
+    Ok(LoxValue::Bool(map.borrow_mut().remove(&key).is_some()))
+}
@@ -0,0 +1,138 @@
+use super::{NativeFunction, number_arg};
+use crate::{
+    bignum::{BigInt, Decimal},
+    error::RloxError,
+    interpreter::Interpreter,
+    value::{LoxValue, float_to_int},
+};
+
+/// Most values `min` and `max` compare, matching the limit on call arguments.
+const MAX_VALUES: usize = 255;
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "floor",
+            arity: 1..=1,
+            capability: None,
+            function: floor,
+        },
+        NativeFunction {
+            name: "ceil",
+            arity: 1..=1,
+            capability: None,
+            function: ceil,
+        },
+        NativeFunction {
+            name: "sqrt",
+            arity: 1..=1,
+            capability: None,
+            function: sqrt,
+        },
+        NativeFunction {
+            name: "pow",
+            arity: 2..=2,
+            capability: None,
+            function: pow,
+        },
+        NativeFunction {
+            name: "abs",
+            arity: 1..=1,
+            capability: None,
+            function: abs,
+        },
+        NativeFunction {
+            name: "min",
+            arity: 1..=MAX_VALUES,
+            capability: None,
+            function: min,
+        },
+        NativeFunction {
+            name: "max",
+            arity: 1..=MAX_VALUES,
+            capability: None,
+            function: max,
+        },
+    ]
+}
+
+/// `floor(x)`: largest integer less than or equal to `x`.
+fn floor(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    round("floor", arguments, f64::floor, Decimal::floor)
+}
+
+/// `ceil(x)`: smallest integer greater than or equal to `x`.
+fn ceil(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    round("ceil", arguments, f64::ceil, Decimal::ceil)
+}
+
+/// Round a number to an integer, staying a float if out of the integers' range.
+/// Big integers are already integers and decimals round exactly to one.
+fn round(
+    name: &str,
+    arguments: &[LoxValue],
+    f: fn(f64) -> f64,
+    exact: fn(&Decimal) -> BigInt,
+) -> Result<LoxValue, RloxError> {
+    match &arguments[0] {
+        value @ (LoxValue::Int(_) | LoxValue::BigInt(_)) => return Ok(value.clone()),
+        LoxValue::Decimal(n) => return Ok(LoxValue::BigInt(exact(n))),
+        _ => {}
+    }
+    let rounded = f(number_arg(name, arguments, 0)?);
+    Ok(float_to_int(rounded).map_or(LoxValue::Number(rounded), LoxValue::Int))
+}
+
+/// `sqrt(x)`: square root of `x`, NaN if negative.
+fn sqrt(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    Ok(LoxValue::Number(number_arg("sqrt", arguments, 0)?.sqrt()))
+}
+
+/// `pow(x, y)`: `x` raised to the power `y`.
+fn pow(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let x = number_arg("pow", arguments, 0)?;
+    let y = number_arg("pow", arguments, 1)?;
+    Ok(LoxValue::Number(x.powf(y)))
+}
+
+/// `abs(x)`: absolute value of `x`.
+fn abs(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    match arguments[0] {
+        LoxValue::Int(n) => n
+            .checked_abs()
+            .map(LoxValue::Int)
+            .ok_or_else(|| RloxError::RuntimeError("Integer overflow in abs().".to_owned())),
+        LoxValue::BigInt(ref n) => Ok(LoxValue::BigInt(n.abs())),
+        LoxValue::Decimal(ref n) => Ok(LoxValue::Decimal(n.abs())),
+        _ => Ok(LoxValue::Number(number_arg("abs", arguments, 0)?.abs())),
+    }
+}
+
+/// `min(x, ...)`: smallest of the given numbers.
+fn min(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    fold("min", arguments, |x, y| x < y)
+}
+
+/// `max(x, ...)`: largest of the given numbers.
+fn max(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    fold("max", arguments, |x, y| x > y)
+}
+
+/// Keep the given number that is `better` than all others, ints staying ints.
+/// NaN is ignored unless all numbers are, as with `f64::min` and `f64::max`.
+fn fold(
+    name: &str,
+    arguments: &[LoxValue],
+    better: fn(f64, f64) -> bool,
+) -> Result<LoxValue, RloxError> {
+    let mut result = &arguments[0];
+    let mut best = number_arg(name, arguments, 0)?;
+    for index in 1..arguments.len() {
+        let number = number_arg(name, arguments, index)?;
+        if better(number, best) || best.is_nan() {
+            result = &arguments[index];
+            best = number;
+        }
+    }
+    Ok(result.clone())
+}
@@ -0,0 +1,231 @@
+mod conversion;
+mod fs;
+mod io;
+mod list;
+mod map;
+mod math;
+mod string;
+mod system;
+
+use std::{fmt, ops::RangeInclusive, rc::Rc, str::FromStr};
+
+use crate::{
+    environment::Environment,
+    error::RloxError,
+    interpreter::Interpreter,
+    value::{List, LoxValue, MapRef},
+};
+
+/// Signature of a function implemented in Rust and callable from lox.
+pub type NativeFn = fn(&mut Interpreter, &[LoxValue]) -> Result<LoxValue, RloxError>;
+
+/// Host resource a native function needs access to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Console input and output besides `print`.
+    Io,
+    /// Reading and writing files.
+    Fs,
+    /// Environment variables.
+    Env,
+    /// Clocks.
+    Time,
+    /// Controlling the running process.
+    Process,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 5] = [
+        Capability::Io,
+        Capability::Fs,
+        Capability::Env,
+        Capability::Time,
+        Capability::Process,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Capability::Io => "io",
+            Capability::Fs => "fs",
+            Capability::Env => "env",
+            Capability::Time => "time",
+            Capability::Process => "process",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl FromStr for Capability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Capability::ALL
+            .into_iter()
+            .find(|capability| capability.as_str() == s)
+            .ok_or_else(|| format!("Unknown capability: {s}."))
+    }
+}
+
+/// Set of capabilities granted to an `Interpreter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    /// No capability, for untrusted scripts.
+    pub fn none() -> Self {
+        Self(0)
+    }
+
+    /// Every capability, for trusted scripts.
+    pub fn all() -> Self {
+        Capability::ALL.into_iter().fold(Self::none(), Self::with)
+    }
+
+    pub fn with(self, capability: Capability) -> Self {
+        Self(self.0 | capability.bit())
+    }
+
+    pub fn without(self, capability: Capability) -> Self {
+        Self(self.0 & !capability.bit())
+    }
+
+    pub fn allows(self, capability: Capability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+}
+
+/// A builtin function implemented in Rust.
+pub struct NativeFunction {
+    pub name: &'static str,
+    /// Accepted number of arguments.
+    pub arity: RangeInclusive<usize>,
+    /// Capability required to call the function, if any.
+    pub capability: Option<Capability>,
+    pub function: NativeFn,
+}
+
+impl NativeFunction {
+    /// Call the function, failing if it needs a capability the interpreter lacks.
+    pub fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: &[LoxValue],
+    ) -> Result<LoxValue, RloxError> {
+        if let Some(capability) = self.capability
+            && !interpreter.capabilities().allows(capability)
+        {
+            return Err(RloxError::RuntimeError(format!(
+                "{}() requires the '{}' capability, which is denied.",
+                self.name,
+                capability.as_str()
+            )));
+        }
+        (self.function)(interpreter, arguments)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<native fn {}>", self.name)
+    }
+}
+
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+/// Define all builtin functions in the given (global) environment.
+pub fn define_globals(environment: &mut Environment) {
+    let natives = [NativeFunction {
+        name: "assert",
+        arity: 1..=2,
+        capability: None,
+        function: assert,
+    }]
+    .into_iter()
+    .chain(string::natives())
+    .chain(list::natives())
+    .chain(map::natives())
+    .chain(math::natives())
+    .chain(conversion::natives())
+    .chain(io::natives())
+    .chain(fs::natives())
+    .chain(system::natives());
+    for native in natives {
+        environment.define(native.name, LoxValue::NativeFunction(Rc::new(native)));
+    }
+}
+
+/// `assert(condition, message?)`: raise a runtime error if condition is not truthy.
+fn assert(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    if arguments[0].is_truthy() {
+        return Ok(LoxValue::Nil);
+    }
+    match arguments.get(1) {
+        Some(message) => Err(RloxError::RuntimeError(format!(
+            "Assertion failed: {}",
+            message
+        ))),
+        None => Err(RloxError::RuntimeError("Assertion failed.".to_owned())),
+    }
+}
+
+/// Error for an argument of the wrong type, counting arguments from 1.
+fn type_error(name: &str, index: usize, expected: &str, value: &LoxValue) -> RloxError {
+    RloxError::RuntimeError(format!(
+        "{}() argument {} must be a {}, got {}.",
+        name,
+        index + 1,
+        expected,
+        value.type_name()
+    ))
+}
+
+/// Return the argument at `index` if it is a string.
+fn string_arg<'a>(
+    name: &str,
+    arguments: &'a [LoxValue],
+    index: usize,
+) -> Result<&'a str, RloxError> {
+    match &arguments[index] {
+        LoxValue::String(s) => Ok(s),
+        value => Err(type_error(name, index, "string", value)),
+    }
+}
+
+/// Return the argument at `index` if it is a list.
+fn list_arg(name: &str, arguments: &[LoxValue], index: usize) -> Result<List, RloxError> {
+    match &arguments[index] {
+        LoxValue::List(list) => Ok(Rc::clone(list)),
+        value => Err(type_error(name, index, "list", value)),
+    }
+}
+
+/// Return the argument at `index` if it is a map.
+fn map_arg(name: &str, arguments: &[LoxValue], index: usize) -> Result<MapRef, RloxError> {
+    match &arguments[index] {
+        LoxValue::Map(map) => Ok(Rc::clone(map)),
+        value => Err(type_error(name, index, "map", value)),
+    }
+}
+
+/// Return the argument at `index` if it is a number, as a float.
+fn number_arg(name: &str, arguments: &[LoxValue], index: usize) -> Result<f64, RloxError> {
+    let value = &arguments[index];
+    value
+        .to_f64()
+        .ok_or_else(|| type_error(name, index, "number", value))
+}
+
+/// Return the argument at `index` if it is a non negative integer.
+fn index_arg(name: &str, arguments: &[LoxValue], index: usize) -> Result<usize, RloxError> {
+    match &arguments[index] {
+        LoxValue::Int(n) if *n >= 0 => Ok(*n as usize),
+        value => Err(type_error(name, index, "non negative integer", value)),
+    }
+}
@@ -0,0 +1,101 @@
+use super::{NativeFunction, index_arg, string_arg, type_error};
+use crate::{error::RloxError, interpreter::Interpreter, value::LoxValue};
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "len",
+            arity: 1..=1,
+            capability: None,
+            function: len,
+        },
+        NativeFunction {
+            name: "substr",
+            arity: 2..=3,
+            capability: None,
+            function: substr,
+        },
+        NativeFunction {
+            name: "indexOf",
+            arity: 2..=2,
+            capability: None,
+            function: index_of,
+        },
+        NativeFunction {
+            name: "upper",
+            arity: 1..=1,
+            capability: None,
+            function: upper,
+        },
+        NativeFunction {
+            name: "lower",
+            arity: 1..=1,
+            capability: None,
+            function: lower,
+        },
+        NativeFunction {
+            name: "trim",
+            arity: 1..=1,
+            capability: None,
+            function: trim,
+        },
+    ]
+}
+
+/// `len(v)`: number of characters of a string, or elements of a list or map.
+fn len(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let len = match &arguments[0] {
+        LoxValue::String(s) => s.chars().count(),
+        LoxValue::List(list) => list.borrow().len(),
+        LoxValue::Map(map) => map.borrow().len(),
+        value => return Err(type_error("len", 0, "string, list or map", value)),
+    };
+    Ok(LoxValue::Int(len as i64))
+}
+
+/// `substr(s, start, length?)`: characters of `s` from `start`, up to its end
+/// or `length` characters.
+fn substr(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let s = string_arg("substr", arguments, 0)?;
+    let start = index_arg("substr", arguments, 1)?;
+    let length = match arguments.get(2) {
+        Some(_) => index_arg("substr", arguments, 2)?,
+        None => usize::MAX,
+    };
+    let count = s.chars().count();
+    if start > count {
+        return Err(RloxError::RuntimeError(format!(
+            "substr() start {start} is out of range for a string of length {count}."
+        )));
+    }
+    interpreter.new_string(s.chars().skip(start).take(length).collect())
+}
+
+/// `indexOf(s, sub)`: index of the first character of `sub` in `s`, -1 if not found.
+fn index_of(_: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let s = string_arg("indexOf", arguments, 0)?;
+    let sub = string_arg("indexOf", arguments, 1)?;
+    let index = match s.find(sub) {
+        Some(byte) => s[..byte].chars().count() as i64,
+        None => -1,
+    };
+    Ok(LoxValue::Int(index))
+}
+
+/// `upper(s)`: `s` in upper case.
+fn upper(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let s = string_arg("upper", arguments, 0)?;
+    interpreter.new_string(s.to_uppercase())
+}
+
+/// `lower(s)`: `s` in lower case.
+fn lower(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let s = string_arg("lower", arguments, 0)?;
+    interpreter.new_string(s.to_lowercase())
+}
+
+/// `trim(s)`: `s` without leading and trailing whitespace.
+fn trim(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let s = string_arg("trim", arguments, 0)?;
+    interpreter.new_string(s.trim().to_owned())
+}
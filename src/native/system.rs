@@ -0,0 +1,91 @@
+use std::{
+    env,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{Capability, NativeFunction, index_arg, string_arg};
+use crate::{error::RloxError, interpreter::Interpreter, value::LoxValue};
+
+/// Largest exit status, as Unix only keeps the low byte of larger ones.
+const MAX_STATUS: i64 = 255;
+
+pub(super) fn natives() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "clock",
+            arity: 0..=0,
+            capability: Some(Capability::Time),
+            function: clock,
+        },
+        NativeFunction {
+            name: "getenv",
+            arity: 1..=1,
+            capability: Some(Capability::Env),
+            function: getenv,
+        },
+        NativeFunction {
+            name: "args",
+            arity: 0..=1,
+            capability: Some(Capability::Env),
+            function: args,
+        },
+        NativeFunction {
+            name: "exit",
+            arity: 0..=1,
+            capability: Some(Capability::Process),
+            function: exit,
+        },
+    ]
+}
+
+/// `clock()`: seconds elapsed since the Unix epoch.
+fn clock(_: &mut Interpreter, _: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| RloxError::RuntimeError(e.to_string()))?;
+    Ok(LoxValue::Number(now.as_secs_f64()))
+}
+
+/// `getenv(name)`: value of an environment variable, nil if unset.
+fn getenv(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let name = string_arg("getenv", arguments, 0)?;
+    match env::var(name) {
+        Ok(value) => interpreter.new_string(value),
+        Err(_) => Ok(LoxValue::Nil),
+    }
+}
+
+/// `args(index?)`: command line argument of the script at `index`, nil past the
+/// last one, or a list of all arguments without `index`.
+fn args(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    if arguments.is_empty() {
+        let args = interpreter.args().iter().cloned().map(LoxValue::String);
+        return Ok(LoxValue::list(args.collect()));
+    }
+    let index = index_arg("args", arguments, 0)?;
+    match interpreter.args().get(index) {
+        Some(arg) => Ok(LoxValue::String(arg.clone())),
+        None => Ok(LoxValue::Nil),
+    }
+}
+
+/// `exit(code?)`: stop the program with the given status, 0 by default. The
+/// host decides what it ends, `rlox` itself exits the process with it.
+fn exit(interpreter: &mut Interpreter, arguments: &[LoxValue]) -> Result<LoxValue, RloxError> {
+    let code = match arguments.first() {
+        None => 0,
+        Some(LoxValue::Int(code)) if (0..=MAX_STATUS).contains(code) => *code as i32,
+        Some(LoxValue::Int(code)) => {
+            return Err(RloxError::RuntimeError(format!(
+                "exit() status must be between 0 and {MAX_STATUS}, got {code}."
+            )));
+        }
+        Some(_) => {
+            return Err(RloxError::RuntimeError(
+                "exit() expects an integer status.".to_owned(),
+            ));
+        }
+    };
+    interpreter.flush_output()?;
+    Err(RloxError::Exit(code))
+}
@@ -1,15 +1,31 @@
+use std::rc::Rc;
+
 use unescape::unescape;
 
 use crate::ast::expr::Expr;
 use crate::ast::stmt::Stmt;
+use crate::bignum::BigInt;
 use crate::error::{RloxError, report};
 use crate::token::{LiteralType, Token, TokenType};
 
+/// Maximum number of arguments in a call expression, and of parameters of a function.
+const MAX_ARGUMENTS: usize = 255;
+/// Maximum nesting of expressions and statements, keeping recursive descent off the stack limit.
+const MAX_NESTING: usize = 128;
+
 #[derive(Debug)]
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// Current nesting of expressions and statements.
+    depth: usize,
+    /// Number of loops enclosing the statement being parsed.
+    loops: usize,
+    /// Number of functions enclosing the statement being parsed.
+    functions: usize,
     pub had_error: bool,
+    /// Errors reported while parsing.
+    pub errors: Vec<RloxError>,
 }
 
 impl Parser {
@@ -17,7 +33,11 @@ impl Parser {
         Self {
             tokens,
             current: 0,
+            depth: 0,
+            loops: 0,
+            functions: 0,
             had_error: false,
+            errors: vec![],
         }
     }
 
@@ -37,8 +57,30 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> Result<Expr, RloxError> {
+        self.nested(Self::nested_assignment)
+    }
+
+    fn nested_assignment(&mut self) -> Result<Expr, RloxError> {
         let expr = self.ternary()?;
 
+        if self.matches(&[
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+        ]) {
+            let operator = self.previous().clone();
+            let value = self.assignment()?;
+            if !is_assignable(&expr) {
+                return Err(self.error(&format!("Invalid assignment target: {}", operator.lexeme)));
+            }
+            return Ok(Expr::CompoundAssignment {
+                target: Box::new(expr),
+                operator,
+                value: Box::new(value),
+            });
+        }
+
         if self.matches(&[TokenType::Equal]) {
             let equals = self.previous().lexeme.clone();
             let value = self.assignment()?;
@@ -49,6 +91,18 @@ impl Parser {
                         value: Box::new(value),
                     });
                 }
+                Expr::Index {
+                    object,
+                    bracket,
+                    index,
+                } => {
+                    return Ok(Expr::IndexSet {
+                        object,
+                        bracket,
+                        index,
+                        value: Box::new(value),
+                    });
+                }
                 _ => {
                     return Err(self.error(&format!("Invalid assignment target: {}", equals)));
                 }
@@ -92,7 +146,7 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Result<Expr, RloxError> {
-        let mut expr = self.term()?;
+        let mut expr = self.range()?;
 
         while self.matches(&[
             TokenType::Less,
@@ -100,6 +154,86 @@ impl Parser {
             TokenType::Greater,
             TokenType::GreaterEqual,
         ]) {
+            let operator = self.previous().clone();
+            let right = self.range()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Parse `start..end`, which doesn't chain.
+    fn range(&mut self) -> Result<Expr, RloxError> {
+        let expr = self.bit_or()?;
+        if self.matches(&[TokenType::DotDot]) {
+            let operator = self.previous().clone();
+            let right = self.bit_or()?;
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_or(&mut self) -> Result<Expr, RloxError> {
+        let mut expr = self.bit_xor()?;
+
+        while self.matches(&[TokenType::Pipe]) {
+            let operator = self.previous().clone();
+            let right = self.bit_xor()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_xor(&mut self) -> Result<Expr, RloxError> {
+        let mut expr = self.bit_and()?;
+
+        while self.matches(&[TokenType::Caret]) {
+            let operator = self.previous().clone();
+            let right = self.bit_and()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_and(&mut self) -> Result<Expr, RloxError> {
+        let mut expr = self.shift()?;
+
+        while self.matches(&[TokenType::Ampersand]) {
+            let operator = self.previous().clone();
+            let right = self.shift()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn shift(&mut self) -> Result<Expr, RloxError> {
+        let mut expr = self.term()?;
+
+        while self.matches(&[TokenType::LessLess, TokenType::GreaterGreater]) {
             let operator = self.previous().clone();
             let right = self.term()?;
             expr = Expr::Binary {
@@ -131,7 +265,12 @@ impl Parser {
     fn factor(&mut self) -> Result<Expr, RloxError> {
         let mut expr = self.unary()?;
 
-        while self.matches(&[TokenType::Star, TokenType::Slash]) {
+        while self.matches(&[
+            TokenType::Star,
+            TokenType::Slash,
+            TokenType::Percent,
+            TokenType::TildeSlash,
+        ]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
             expr = Expr::Binary {
@@ -145,16 +284,135 @@ impl Parser {
     }
 
     fn unary(&mut self) -> Result<Expr, RloxError> {
-        if self.matches(&[TokenType::Bang, TokenType::Minus]) {
+        if self.matches(&[TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
             let operator = self.previous().clone();
-            let right = self.unary()?;
+            if operator.token_type == TokenType::Minus && self.check_min_int() {
+                self.advance();
+                return Ok(Expr::Literal {
+                    value: LiteralType::Int(i64::MIN),
+                });
+            }
+            let right = self.nested(Self::unary)?;
             return Ok(Expr::Unary {
                 operator,
                 right: Box::new(right),
             });
         }
 
-        self.primary()
+        self.power()
+    }
+
+    /// Whether the next token is `9223372036854775808` as the whole operand of a
+    /// minus, the only way to write the smallest int.
+    fn check_min_int(&self) -> bool {
+        let token = self.peek();
+        token.token_type == TokenType::Number
+            && is_oversized_int(token)
+            && token.literal == LiteralType::BigInt(-&BigInt::from(i64::MIN))
+            && !matches!(
+                self.peek_next().token_type,
+                TokenType::StarStar
+                    | TokenType::LeftParen
+                    | TokenType::LeftBracket
+                    | TokenType::PlusPlus
+                    | TokenType::MinusMinus
+            )
+    }
+
+    /// Parse `base ** exponent`, right-associative and binding tighter than a unary
+    /// operator on its left: `-2 ** 2` is `-(2 ** 2)`, while `2 ** -1` is allowed.
+    fn power(&mut self) -> Result<Expr, RloxError> {
+        let expr = self.update()?;
+        if self.matches(&[TokenType::StarStar]) {
+            let operator = self.previous().clone();
+            let right = self.nested(Self::unary)?;
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    /// Parse a prefix `++a` or postfix `a--` on a variable or index target.
+    fn update(&mut self) -> Result<Expr, RloxError> {
+        let prefix = self.matches(&[TokenType::PlusPlus, TokenType::MinusMinus]);
+        let operator = prefix.then(|| self.previous().clone());
+        let target = self.call()?;
+        let operator = match operator {
+            Some(operator) => operator,
+            None if self.matches(&[TokenType::PlusPlus, TokenType::MinusMinus]) => {
+                self.previous().clone()
+            }
+            None => return Ok(target),
+        };
+        if !is_assignable(&target) {
+            return Err(self.error(&format!("Invalid assignment target: {}", operator.lexeme)));
+        }
+
+        Ok(Expr::Update {
+            operator,
+            target: Box::new(target),
+            prefix,
+        })
+    }
+
+    fn call(&mut self) -> Result<Expr, RloxError> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.matches(&[TokenType::LeftParen]) {
+                expr = self.finish_call(expr)?;
+            } else if self.matches(&[TokenType::LeftBracket]) {
+                let bracket = self.previous().clone();
+                let index = self.expression()?;
+                self.consume(TokenType::RightBracket, "Expect ']' after index.")?;
+                expr = Expr::Index {
+                    object: Box::new(expr),
+                    bracket,
+                    index: Box::new(index),
+                };
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, RloxError> {
+        let arguments = self.arguments(TokenType::RightParen, "arguments")?;
+        let paren = self
+            .consume(TokenType::RightParen, "Expect ')' after arguments.")?
+            .clone();
+
+        Ok(Expr::Call {
+            callee: Box::new(callee),
+            paren,
+            arguments,
+        })
+    }
+
+    /// Parse comma separated expressions up to the closing token, left unconsumed.
+    fn arguments(&mut self, close: TokenType, what: &str) -> Result<Vec<Expr>, RloxError> {
+        let mut arguments = vec![];
+        if !self.check(close) {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    return Err(
+                        self.error(&format!("Can't have more than {} {}.", MAX_ARGUMENTS, what))
+                    );
+                }
+                arguments.push(self.expression()?);
+                if !self.matches(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        Ok(arguments)
     }
 
     fn primary(&mut self) -> Result<Expr, RloxError> {
@@ -178,12 +436,14 @@ impl Parser {
                 })
             }
             TokenType::Number => {
-                let lexeme = &self.peek().lexeme;
-                let number = lexeme.as_str().parse::<f64>().unwrap();
+                if is_oversized_int(self.peek()) {
+                    return Err(self.error(
+                        "Integer literal out of range, add an 'n' suffix for a big integer.",
+                    ));
+                }
+                let value = self.peek().literal.clone();
                 self.advance();
-                Ok(Expr::Literal {
-                    value: LiteralType::Number(number),
-                })
+                Ok(Expr::Literal { value })
             }
             TokenType::String => {
                 let lexeme = self.peek().lexeme.clone();
@@ -196,11 +456,13 @@ impl Parser {
                         })
                     }
                     None => {
-                        report(&RloxError::LexicalError(
+                        let error = RloxError::LexicalError(
                             self.peek().line,
                             "Invalid escape string sequence".to_string(),
                             lexeme.clone(),
-                        ));
+                        );
+                        report(&error);
+                        self.errors.push(error);
                         self.advance();
                         self.had_error = true;
                         Ok(Expr::Literal {
@@ -209,6 +471,31 @@ impl Parser {
                     }
                 }
             }
+            TokenType::Fun => {
+                let keyword = self.advance().clone();
+                self.consume(TokenType::LeftParen, "Expect '(' after 'fun'.")?;
+                let params = self.parameters()?;
+                self.consume(TokenType::LeftBrace, "Expect '{' before function body.")?;
+                let body = self.function_body(Self::block)?;
+                Ok(Expr::Lambda {
+                    keyword,
+                    params,
+                    body: Rc::new(body),
+                })
+            }
+            TokenType::LeftParen if self.check_arrow() => {
+                self.advance();
+                let params = self.parameters()?;
+                let keyword = self
+                    .consume(TokenType::Arrow, "Expect '=>' after parameters.")?
+                    .clone();
+                let value = self.function_body(Self::expression)?;
+                Ok(Expr::Lambda {
+                    body: Rc::new(Stmt::Return(keyword.clone(), Some(value))),
+                    keyword,
+                    params,
+                })
+            }
             TokenType::LeftParen => {
                 self.advance();
                 let expr = self.expression()?;
@@ -221,6 +508,28 @@ impl Parser {
                 let name = self.advance().clone();
                 Ok(Expr::Variable { name })
             }
+            TokenType::LeftBrace => {
+                let brace = self.advance().clone();
+                let mut entries = vec![];
+                if !self.check(TokenType::RightBrace) {
+                    loop {
+                        let key = self.expression()?;
+                        self.consume(TokenType::Colon, "Expect ':' after map key.")?;
+                        entries.push((key, self.expression()?));
+                        if !self.matches(&[TokenType::Comma]) {
+                            break;
+                        }
+                    }
+                }
+                self.consume(TokenType::RightBrace, "Expect '}' after map entries.")?;
+                Ok(Expr::Map { brace, entries })
+            }
+            TokenType::LeftBracket => {
+                let bracket = self.advance().clone();
+                let elements = self.arguments(TokenType::RightBracket, "elements")?;
+                self.consume(TokenType::RightBracket, "Expect ']' after list elements.")?;
+                Ok(Expr::List { bracket, elements })
+            }
             _ => Err(self.error(&format!(
                 "Unexpected token type: {:?}.",
                 self.peek().token_type
@@ -229,12 +538,71 @@ impl Parser {
     }
 }
 
+/// Methods for parsing functions.
+impl Parser {
+    /// Whether a `(` starts the parameters of an arrow function, that is when
+    /// followed by comma separated identifiers, `)` then `=>`.
+    fn check_arrow(&self) -> bool {
+        let mut i = self.current + 1;
+        let token_type = |i: usize| self.tokens.get(i).map(|token| token.token_type);
+        if token_type(i) == Some(TokenType::Identifier) {
+            i += 1;
+            while token_type(i) == Some(TokenType::Comma)
+                && token_type(i + 1) == Some(TokenType::Identifier)
+            {
+                i += 2;
+            }
+        }
+        token_type(i) == Some(TokenType::RightParen) && token_type(i + 1) == Some(TokenType::Arrow)
+    }
+
+    /// Parse parameter names and the closing `)`, after the `(`.
+    fn parameters(&mut self) -> Result<Vec<Token>, RloxError> {
+        let mut params = vec![];
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    return Err(
+                        self.error(&format!("Can't have more than {MAX_ARGUMENTS} parameters."))
+                    );
+                }
+                let param = self.consume(TokenType::Identifier, "Expect parameter name.")?;
+                params.push(param.clone());
+                if !self.matches(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RightParen, "Expect ')' after parameters.")?;
+
+        Ok(params)
+    }
+
+    /// Parse a function body, where `return` is allowed and enclosing loops are out of reach.
+    fn function_body<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, RloxError>,
+    ) -> Result<T, RloxError> {
+        let loops = std::mem::take(&mut self.loops);
+        self.functions += 1;
+        let result = parse(self);
+        self.functions -= 1;
+        self.loops = loops;
+        result
+    }
+}
+
 /// Methods for parsing statements.
 impl Parser {
     fn program(&mut self) -> Option<Stmt> {
         let mut statements = vec![];
         while !self.had_error && !self.is_at_end() {
-            if let Some(stmt) = self.declaration() {
+            let stmt = if self.check_test_declaration() {
+                self.test_declaration()
+            } else {
+                self.declaration()
+            };
+            if let Some(stmt) = stmt {
                 statements.push(stmt);
             }
         }
@@ -242,6 +610,39 @@ impl Parser {
         Some(Stmt::Program(statements))
     }
 
+    /// Return whether current tokens start a `test "name" { ... }` block.
+    /// `test` is only treated specially at top level, so it stays usable as an identifier.
+    fn check_test_declaration(&self) -> bool {
+        self.check(TokenType::Identifier)
+            && self.peek().lexeme == "test"
+            && self.peek_next().token_type == TokenType::String
+    }
+
+    fn test_declaration(&mut self) -> Option<Stmt> {
+        match self.test_body() {
+            Ok(stmt) => Some(stmt),
+            Err(_) => {
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    fn test_body(&mut self) -> Result<Stmt, RloxError> {
+        // Skip the `test` identifier.
+        self.advance();
+        let lexeme = &self.peek().lexeme;
+        let Some(unescaped) = unescape(&lexeme[1..lexeme.len() - 1]) else {
+            return Err(self.error("Invalid escape string sequence."));
+        };
+        let mut name = self.advance().clone();
+        name.literal = LiteralType::String(unescaped);
+        self.consume(TokenType::LeftBrace, "Expect '{' before test body.")?;
+        let body = self.block()?;
+
+        Ok(Stmt::Test(name, Box::new(body)))
+    }
+
     fn declaration(&mut self) -> Option<Stmt> {
         match if self.matches(&[TokenType::Var]) {
             self.var_declaration()
@@ -274,37 +675,156 @@ impl Parser {
     }
 
     fn statement(&mut self) -> Result<Stmt, RloxError> {
+        self.nested(Self::nested_statement)
+    }
+
+    fn nested_statement(&mut self) -> Result<Stmt, RloxError> {
         if self.matches(&[TokenType::Print]) {
             self.print_statement()
-        } else if self.matches(&[TokenType::LeftBrace]) {
+        } else if self.matches(&[TokenType::If]) {
+            self.if_statement()
+        } else if self.matches(&[TokenType::While]) {
+            self.while_statement()
+        } else if self.matches(&[TokenType::For]) {
+            self.for_in_statement()
+        } else if self.check(TokenType::Break) || self.check(TokenType::Continue) {
+            self.loop_control_statement()
+        } else if self.check(TokenType::Return) {
+            self.return_statement()
+        } else if self.check(TokenType::LeftBrace) && !self.check_map() {
+            self.advance();
             self.block()
         } else {
             self.expression_statement()
         }
     }
 
+    /// Whether a `{` starting a statement opens a map rather than a block,
+    /// that is when followed by a literal or variable key then `:`.
+    fn check_map(&self) -> bool {
+        let key = self.peek_next().token_type;
+        let colon = self
+            .tokens
+            .get(self.current + 2)
+            .is_some_and(|token| token.token_type == TokenType::Colon);
+        colon
+            && matches!(
+                key,
+                TokenType::String
+                    | TokenType::Number
+                    | TokenType::True
+                    | TokenType::False
+                    | TokenType::Nil
+                    | TokenType::Identifier
+            )
+    }
+
     fn block(&mut self) -> Result<Stmt, RloxError> {
+        let line = self.previous().line;
         let mut statements = vec![];
         while !self.check(TokenType::RightBrace) && !self.is_at_end() {
             if let Some(stmt) = self.declaration() {
                 statements.push(stmt);
             }
         }
-        self.consume(TokenType::RightBrace, "Expect '}' after block.")?;
+        let end_line = self
+            .consume(TokenType::RightBrace, "Expect '}' after block.")?
+            .line;
 
-        Ok(Stmt::Block(statements))
+        Ok(Stmt::Block(statements, line, end_line))
     }
 
     fn expression_statement(&mut self) -> Result<Stmt, RloxError> {
+        let line = self.peek().line;
         let expr = self.expression()?;
         self.consume(TokenType::Semicolon, "Expect ';' after expression")?;
-        Ok(Stmt::Expression(expr))
+        Ok(Stmt::Expression(expr, line))
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, RloxError> {
+        let line = self.previous().line;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after condition.")?;
+        self.loops += 1;
+        let body = self.statement();
+        self.loops -= 1;
+
+        Ok(Stmt::While(condition, Box::new(body?), line))
+    }
+
+    fn for_in_statement(&mut self) -> Result<Stmt, RloxError> {
+        let line = self.previous().line;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'for'.")?;
+        self.consume(TokenType::Var, "Expect 'var' after 'for ('.")?;
+        let name = self
+            .consume(TokenType::Identifier, "Expect variable name.")?
+            .clone();
+        self.consume(TokenType::In, "Expect 'in' after variable name.")?;
+        let iterable = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after for clauses.")?;
+        self.loops += 1;
+        let body = self.statement();
+        self.loops -= 1;
+
+        Ok(Stmt::ForIn(name, iterable, Box::new(body?), line))
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, RloxError> {
+        let line = self.previous().line;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'if'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after if condition.")?;
+        let then_branch = self.statement()?;
+        let else_branch = if self.matches(&[TokenType::Else]) {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(Stmt::If(
+            condition,
+            Box::new(then_branch),
+            else_branch,
+            line,
+        ))
+    }
+
+    /// Parse `break;` or `continue;`, only allowed inside a loop.
+    fn loop_control_statement(&mut self) -> Result<Stmt, RloxError> {
+        if self.loops == 0 {
+            let message = format!("Can't use '{}' outside of a loop.", self.peek().lexeme);
+            return Err(self.error(&message));
+        }
+        let keyword = self.advance().clone();
+        let message = format!("Expect ';' after '{}'.", keyword.lexeme);
+        self.consume(TokenType::Semicolon, &message)?;
+
+        Ok(match keyword.token_type {
+            TokenType::Break => Stmt::Break(keyword),
+            _ => Stmt::Continue(keyword),
+        })
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, RloxError> {
+        if self.functions == 0 {
+            return Err(self.error("Can't return from top-level code."));
+        }
+        let keyword = self.advance().clone();
+        let mut value = None;
+        if !self.check(TokenType::Semicolon) {
+            value = Some(self.expression()?);
+        }
+        self.consume(TokenType::Semicolon, "Expect ';' after return value.")?;
+
+        Ok(Stmt::Return(keyword, value))
     }
 
     fn print_statement(&mut self) -> Result<Stmt, RloxError> {
+        let line = self.previous().line;
         let value = self.expression()?;
         self.consume(TokenType::Semicolon, "Expect ';' after value")?;
-        Ok(Stmt::Print(value))
+        Ok(Stmt::Print(value, line))
     }
 }
 
@@ -350,6 +870,15 @@ impl Parser {
         &self.tokens[self.current]
     }
 
+    /// Return the token after current token.
+    fn peek_next(&self) -> &Token {
+        if self.is_at_end() {
+            self.peek()
+        } else {
+            &self.tokens[self.current + 1]
+        }
+    }
+
     /// Return previous token.
     fn previous(&self) -> &Token {
         &self.tokens[self.current - 1]
@@ -364,6 +893,20 @@ impl Parser {
         }
     }
 
+    /// Run a parsing method one nesting level deeper, failing past `MAX_NESTING`.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, RloxError>,
+    ) -> Result<T, RloxError> {
+        if self.depth >= MAX_NESTING {
+            return Err(self.error("Too much nesting."));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
     /// Return a syntax error.
     fn error(&mut self, message: &str) -> RloxError {
         self.had_error = true;
@@ -373,6 +916,7 @@ impl Parser {
             message.to_owned(),
         );
         report(&error);
+        self.errors.push(error.clone());
 
         error
     }
@@ -398,8 +942,18 @@ impl Parser {
                 }
                 _ => {}
             }
-        }
 
-        self.advance();
+            self.advance();
+        }
     }
 }
+
+/// Whether an expression can be the target of a compound assignment or update.
+fn is_assignable(expr: &Expr) -> bool {
+    matches!(expr, Expr::Variable { .. } | Expr::Index { .. })
+}
+
+/// Whether a number token is an integer without the `n` suffix too large for an int.
+fn is_oversized_int(token: &Token) -> bool {
+    matches!(token.literal, LiteralType::BigInt(_)) && !token.lexeme.ends_with('n')
+}
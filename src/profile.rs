@@ -0,0 +1,156 @@
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt::Write,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::{ast::stmt::Stmt, environment::Environment, error::RloxError, interpreter::Hook};
+
+/// Execution count and wall time of statements.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub count: u64,
+    /// Time spent in the statements, including nested ones.
+    pub total: Duration,
+    /// Time spent in the statements, excluding nested ones.
+    pub own: Duration,
+}
+
+impl Stats {
+    fn add(&mut self, total: Duration, own: Duration) {
+        self.count += 1;
+        self.total += total;
+        self.own += own;
+    }
+}
+
+/// Statistics collected by a `Profiler`.
+#[derive(Debug, Default)]
+pub struct Profile {
+    pub lines: BTreeMap<usize, Stats>,
+    pub kinds: BTreeMap<&'static str, Stats>,
+    /// Own time of each stack of statements, frames joined by `;`.
+    pub stacks: BTreeMap<String, Duration>,
+}
+
+impl Profile {
+    /// Hot spot report, lines and statement kinds sorted by own time.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        out.push_str("Hot spots by line:\n");
+        table(&mut out, "line", &self.lines);
+        out.push_str("\nHot spots by statement kind:\n");
+        table(&mut out, "kind", &self.kinds);
+        out
+    }
+
+    /// Stacks in the folded format read by flamegraph tools, weighted by
+    /// own time in microseconds.
+    pub fn folded(&self) -> String {
+        let mut out = String::new();
+        for (stack, own) in &self.stacks {
+            writeln!(out, "{} {}", stack, own.as_micros()).unwrap();
+        }
+        out
+    }
+}
+
+fn table<K: ToString>(out: &mut String, name: &str, stats: &BTreeMap<K, Stats>) {
+    let mut rows: Vec<(&K, &Stats)> = stats.iter().collect();
+    // Stable sort keeps keys in order for equal times.
+    rows.sort_by_key(|(_, stats)| Reverse(stats.own));
+    writeln!(
+        out,
+        "{:>12} {:>10} {:>12} {:>12}",
+        name, "count", "own ms", "total ms"
+    )
+    .unwrap();
+    for (key, stats) in rows {
+        writeln!(
+            out,
+            "{:>12} {:>10} {:>12.3} {:>12.3}",
+            key.to_string(),
+            stats.count,
+            milliseconds(stats.own),
+            milliseconds(stats.total)
+        )
+        .unwrap();
+    }
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// A statement being executed.
+struct Frame {
+    start: Instant,
+    /// Time spent in nested statements.
+    nested: Duration,
+}
+
+/// Hook counting executions and measuring wall time of each statement.
+///
+/// Results are accumulated in a shared `Profile`, readable once the
+/// interpreter is done.
+pub struct Profiler {
+    profile: Rc<RefCell<Profile>>,
+    stack: Vec<Frame>,
+    /// Names of the frames on the stack, e.g. `Block:2`.
+    names: Vec<String>,
+}
+
+impl Profiler {
+    pub fn new(profile: Rc<RefCell<Profile>>) -> Self {
+        Self {
+            profile,
+            stack: vec![],
+            names: vec![],
+        }
+    }
+}
+
+impl Hook for Profiler {
+    fn before_stmt(
+        &mut self,
+        stmt: &Stmt,
+        _environment: &Environment,
+        _calls: usize,
+    ) -> Result<(), RloxError> {
+        self.names.push(format!("{}:{}", stmt.kind(), stmt.line()));
+        self.stack.push(Frame {
+            start: Instant::now(),
+            nested: Duration::ZERO,
+        });
+        Ok(())
+    }
+
+    fn after_stmt(&mut self, stmt: &Stmt, _environment: &Environment) -> Result<(), RloxError> {
+        let Some(frame) = self.stack.pop() else {
+            return Ok(());
+        };
+        let total = frame.start.elapsed();
+        let own = total.saturating_sub(frame.nested);
+        if let Some(parent) = self.stack.last_mut() {
+            parent.nested += total;
+        }
+
+        let mut profile = self.profile.borrow_mut();
+        profile
+            .lines
+            .entry(stmt.line())
+            .or_default()
+            .add(total, own);
+        profile
+            .kinds
+            .entry(stmt.kind())
+            .or_default()
+            .add(total, own);
+        *profile.stacks.entry(self.names.join(";")).or_default() += own;
+        self.names.pop();
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use std::fs;
-use std::io;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
 
+use crate::ast::formatter::Formatter;
 use crate::error::RloxError;
 use crate::interpreter::Interpreter;
 use crate::parser::Parser;
@@ -8,23 +10,33 @@ use crate::scanner::Scanner;
 
 /// Run lox from source file.
 pub fn run_file(path: &str) -> Result<(), RloxError> {
+    run_file_with(path, &mut Interpreter::new())
+}
+
+/// Run lox from source file with a given, possibly hooked, interpreter.
+pub fn run_file_with(path: &str, interpreter: &mut Interpreter) -> Result<(), RloxError> {
     let content = fs::read_to_string(path)?;
-    let mut interpreter = Interpreter::new();
-    run(&content, &mut interpreter)
+    run(&content, interpreter)
 }
 
 /// Run lox using REPL.
 pub fn run_prompt() -> Result<(), RloxError> {
-    let stdin = io::stdin();
+    run_repl(io::stdin().lock(), &mut Interpreter::new())
+}
+
+/// Run each line read from `input` as a program in the same interpreter, until end of input.
+///
+/// A line failing at runtime is reported and leaves the interpreter ready for the next one.
+pub fn run_repl(mut input: impl BufRead, interpreter: &mut Interpreter) -> Result<(), RloxError> {
     let mut buffer = String::new();
-    let mut interpreter = Interpreter::new();
     loop {
         println!("> ");
         buffer.clear();
-        let len = stdin.read_line(&mut buffer)?;
-        if len > 0 {
-            run(&buffer, &mut interpreter)?
+        let len = input.read_line(&mut buffer)?;
+        if len == 0 {
+            return Ok(());
         }
+        run(&buffer, interpreter)?
     }
 }
 
@@ -33,11 +45,95 @@ fn run(source: &str, interpreter: &mut Interpreter) -> Result<(), RloxError> {
     let tokens = scanner.scan_tokens()?;
     let mut parser = Parser::new(tokens);
 
+    match parser.parse() {
+        Some(program) if !parser.had_error => interpreter.interpret(program),
+        _ => Ok(()),
+    }
+}
+
+/// Serialize tokens of lox source code to JSON.
+#[cfg(feature = "json")]
+pub fn dump_tokens_json(source: &str) -> Result<String, RloxError> {
+    let mut scanner = Scanner::new(source.to_owned());
+    let tokens = scanner.scan_tokens()?;
+    Ok(serde_json::to_string_pretty(&tokens).expect("tokens are serializable"))
+}
+
+/// Serialize the parsed program of lox source code to JSON.
+#[cfg(feature = "json")]
+pub fn dump_ast_json(source: &str) -> Result<String, RloxError> {
+    let mut scanner = Scanner::new(source.to_owned());
+    let tokens = scanner.scan_tokens()?;
+    let mut parser = Parser::new(tokens);
+
     match parser.parse() {
         Some(program) if !parser.had_error => {
-            interpreter.interpret(program);
+            Ok(serde_json::to_string_pretty(&program).expect("AST is serializable"))
+        }
+        _ => Err(RloxError::ParserError),
+    }
+}
+
+/// Format lox source code.
+pub fn format_source(source: &str) -> Result<String, RloxError> {
+    let mut scanner = Scanner::new(source.to_owned());
+    let tokens = scanner.scan_tokens()?;
+    let mut parser = Parser::new(tokens);
+
+    match parser.parse() {
+        Some(program) if !parser.had_error => Ok(Formatter::new(scanner.comments).format(&program)),
+        _ => Err(RloxError::ParserError),
+    }
+}
+
+/// Format all lox files under given paths in place.
+/// With `check`, files are left untouched and only reported.
+/// Return whether all files were already formatted.
+pub fn format_paths(paths: &[String], check: bool) -> Result<bool, RloxError> {
+    let mut formatted = true;
+    for path in paths {
+        for file in find_files(Path::new(path), ".lox")? {
+            let content = fs::read_to_string(&file)?;
+            let output = format_source(&content)?;
+            if output != content {
+                formatted = false;
+                if check {
+                    println!("Unformatted: {}", file.display());
+                } else {
+                    fs::write(&file, output)?;
+                }
+            }
+        }
+    }
+
+    Ok(formatted)
+}
+
+/// Find files whose name ends with `suffix` under the given path, sorted by path.
+/// A path pointing to a file is returned as is.
+pub fn find_files(path: &Path, suffix: &str) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    if path.is_file() {
+        files.push(path.to_path_buf());
+    } else {
+        collect_files(path, suffix, &mut files)?;
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+fn collect_files(dir: &Path, suffix: &str, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, suffix, files)?;
+        } else if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(suffix))
+        {
+            files.push(path);
         }
-        _ => {}
     }
 
     Ok(())
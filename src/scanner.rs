@@ -2,19 +2,22 @@ use std::{collections::HashMap, sync::LazyLock};
 
 use crate::{
     error::{RloxError, report},
-    token::{LiteralType, Token, TokenType},
+    token::{LiteralType, Span, Token, TokenType},
 };
 
 // Lazy init keywords map.
 static KEYWORDS: LazyLock<HashMap<&str, TokenType>> = LazyLock::new(|| {
     let mut keywords = HashMap::with_capacity(30);
     keywords.insert("and", TokenType::And);
+    keywords.insert("break", TokenType::Break);
     keywords.insert("class", TokenType::Class);
+    keywords.insert("continue", TokenType::Continue);
     keywords.insert("else", TokenType::Else);
     keywords.insert("false", TokenType::False);
     keywords.insert("for", TokenType::For);
     keywords.insert("fun", TokenType::Fun);
     keywords.insert("if", TokenType::If);
+    keywords.insert("in", TokenType::In);
     keywords.insert("nil", TokenType::Nil);
     keywords.insert("or", TokenType::Or);
     keywords.insert("print", TokenType::Print);
@@ -28,10 +31,21 @@ static KEYWORDS: LazyLock<HashMap<&str, TokenType>> = LazyLock::new(|| {
     keywords
 });
 
+/// A `//` comment, kept aside from the token stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// Comment text including the leading `//`.
+    pub text: String,
+    pub line: usize,
+}
+
 #[derive(Debug)]
 pub struct Scanner {
     source: String,
     tokens: Vec<Token>,
+    pub comments: Vec<Comment>,
+    /// Lexical errors reported while scanning.
+    pub errors: Vec<RloxError>,
     start: usize,
     current: usize,
     line: usize,
@@ -44,6 +58,8 @@ impl Scanner {
         Self {
             source,
             tokens: vec![],
+            comments: vec![],
+            errors: vec![],
             start: 0,
             current: 0,
             line: 1,
@@ -60,12 +76,17 @@ impl Scanner {
             return Err(RloxError::ScannerError);
         }
 
-        self.tokens.push(Token::new(
+        let mut eof = Token::new(
             TokenType::Eof,
             String::from(""),
             LiteralType::Nil,
             self.line,
-        ));
+        );
+        eof.span = Span {
+            start: self.source.len(),
+            end: self.source.len(),
+        };
+        self.tokens.push(eof);
 
         Ok(self.tokens.to_owned())
     }
@@ -83,22 +104,42 @@ impl Scanner {
             b')' => self.add_token(TokenType::RightParen, LiteralType::Nil),
             b'{' => self.add_token(TokenType::LeftBrace, LiteralType::Nil),
             b'}' => self.add_token(TokenType::RightBrace, LiteralType::Nil),
+            b'[' => self.add_token(TokenType::LeftBracket, LiteralType::Nil),
+            b']' => self.add_token(TokenType::RightBracket, LiteralType::Nil),
             b',' => self.add_token(TokenType::Comma, LiteralType::Nil),
+            b'.' if self.r#match(b'.') => self.add_token(TokenType::DotDot, LiteralType::Nil),
             b'.' => self.add_token(TokenType::Dot, LiteralType::Nil),
-            b'-' => self.add_token(TokenType::Minus, LiteralType::Nil),
-            b'+' => self.add_token(TokenType::Plus, LiteralType::Nil),
             b';' => self.add_token(TokenType::Semicolon, LiteralType::Nil),
-            b'*' => self.add_token(TokenType::Star, LiteralType::Nil),
+            b'%' => self.add_token(TokenType::Percent, LiteralType::Nil),
+            b'&' => self.add_token(TokenType::Ampersand, LiteralType::Nil),
+            b'|' => self.add_token(TokenType::Pipe, LiteralType::Nil),
+            b'^' => self.add_token(TokenType::Caret, LiteralType::Nil),
 
             // One or two characters tokens.
             b'!' if self.r#match(b'=') => self.add_token(TokenType::BangEqual, LiteralType::Nil),
             b'!' => self.add_token(TokenType::Bang, LiteralType::Nil),
             b'=' if self.r#match(b'=') => self.add_token(TokenType::EqualEqual, LiteralType::Nil),
+            b'=' if self.r#match(b'>') => self.add_token(TokenType::Arrow, LiteralType::Nil),
             b'=' => self.add_token(TokenType::Equal, LiteralType::Nil),
             b'<' if self.r#match(b'=') => self.add_token(TokenType::LessEqual, LiteralType::Nil),
+            b'<' if self.r#match(b'<') => self.add_token(TokenType::LessLess, LiteralType::Nil),
             b'<' => self.add_token(TokenType::Less, LiteralType::Nil),
             b'>' if self.r#match(b'=') => self.add_token(TokenType::GreaterEqual, LiteralType::Nil),
+            b'>' if self.r#match(b'>') => {
+                self.add_token(TokenType::GreaterGreater, LiteralType::Nil)
+            }
             b'>' => self.add_token(TokenType::Greater, LiteralType::Nil),
+            b'-' if self.r#match(b'-') => self.add_token(TokenType::MinusMinus, LiteralType::Nil),
+            b'-' if self.r#match(b'=') => self.add_token(TokenType::MinusEqual, LiteralType::Nil),
+            b'-' => self.add_token(TokenType::Minus, LiteralType::Nil),
+            b'+' if self.r#match(b'+') => self.add_token(TokenType::PlusPlus, LiteralType::Nil),
+            b'+' if self.r#match(b'=') => self.add_token(TokenType::PlusEqual, LiteralType::Nil),
+            b'+' => self.add_token(TokenType::Plus, LiteralType::Nil),
+            b'*' if self.r#match(b'*') => self.add_token(TokenType::StarStar, LiteralType::Nil),
+            b'*' if self.r#match(b'=') => self.add_token(TokenType::StarEqual, LiteralType::Nil),
+            b'*' => self.add_token(TokenType::Star, LiteralType::Nil),
+            b'~' if self.r#match(b'/') => self.add_token(TokenType::TildeSlash, LiteralType::Nil),
+            b'~' => self.add_token(TokenType::Tilde, LiteralType::Nil),
 
             // Ternary.
             b'?' => self.add_token(TokenType::QuestionMark, LiteralType::Nil),
@@ -110,6 +151,12 @@ impl Scanner {
                     while self.peek() != b'\n' && !self.is_at_end() {
                         self.advance();
                     }
+                    self.comments.push(Comment {
+                        text: self.source[self.start..self.current].trim_end().to_string(),
+                        line: self.line,
+                    });
+                } else if self.r#match(b'=') {
+                    self.add_token(TokenType::SlashEqual, LiteralType::Nil);
                 } else {
                     self.add_token(TokenType::Slash, LiteralType::Nil);
                 }
@@ -129,7 +176,7 @@ impl Scanner {
                 } else if is_alpha(b) {
                     self.identifier();
                 } else {
-                    report(&RloxError::LexicalError(
+                    self.report(RloxError::LexicalError(
                         self.line,
                         (b as char).to_string(),
                         "invalid token".to_string(),
@@ -139,6 +186,12 @@ impl Scanner {
         }
     }
 
+    /// Report a lexical error and keep it for later inspection.
+    fn report(&mut self, error: RloxError) {
+        report(&error);
+        self.errors.push(error);
+    }
+
     /// Advance current index and return current byte.
     fn advance(&mut self) -> u8 {
         self.current += 1;
@@ -153,6 +206,10 @@ impl Scanner {
             lexeme: text,
             literal,
             line: self.line,
+            span: Span {
+                start: self.start,
+                end: self.current,
+            },
         });
     }
 
@@ -204,7 +261,7 @@ impl Scanner {
 
         if self.is_at_end() {
             self.had_error = true;
-            report(&RloxError::LexicalError(
+            self.report(RloxError::LexicalError(
                 self.line,
                 self.source[self.start..self.current].to_string(),
                 "unterminated string".to_string(),
@@ -225,8 +282,9 @@ impl Scanner {
             self.advance();
         }
 
-        // Look for a fractional part.
-        if self.peek() == b'.' && is_digit(self.peek_next()) {
+        // Look for a fractional part, making the number a float.
+        let float = self.peek() == b'.' && is_digit(self.peek_next());
+        if float {
             // Consume the '.'.
             self.advance();
 
@@ -235,27 +293,54 @@ impl Scanner {
             }
         }
 
+        // A `n` suffix makes an integer big, a `d` suffix makes a number decimal.
+        let suffix = match self.peek() {
+            b'n' if !float => Some(b'n'),
+            b'd' => Some(b'd'),
+            _ => None,
+        };
+        let suffix = suffix.filter(|_| !is_alpha_numeric(self.peek_next()));
+        if suffix.is_some() {
+            self.advance();
+        }
+
         // Make sure number does not have trailing alpha characters.
         if is_alpha(self.peek()) {
             while is_alpha_numeric(self.peek()) {
                 self.advance();
             }
             self.had_error = true;
-            report(&RloxError::LexicalError(
+            self.report(RloxError::LexicalError(
                 self.line,
                 self.source[self.start..self.current].to_string(),
                 "invalid number".to_string(),
             ));
+            return;
         }
 
-        match self.source[self.start..self.current].parse::<f64>() {
-            Ok(number) => self.add_token(TokenType::Number, LiteralType::Number(number)),
-            Err(_) => {
+        let text = &self.source[self.start..self.current];
+        let literal = match suffix {
+            Some(b'n') => text[..text.len() - 1].parse().ok().map(LiteralType::BigInt),
+            Some(_) => text[..text.len() - 1]
+                .parse()
+                .ok()
+                .map(LiteralType::Decimal),
+            None if float => text.parse().ok().map(LiteralType::Number),
+            // The parser reads `-9223372036854775808` as an int and rejects
+            // other integers beyond 64 bits.
+            None => match text.parse() {
+                Ok(n) => Some(LiteralType::Int(n)),
+                Err(_) => text.parse().ok().map(LiteralType::BigInt),
+            },
+        };
+        match literal {
+            Some(literal) => self.add_token(TokenType::Number, literal),
+            None => {
                 self.had_error = true;
-                report(&RloxError::LexicalError(
+                self.report(RloxError::LexicalError(
                     self.line,
                     self.source[self.start..self.current].to_string(),
-                    "invalid number".to_string(),
+                    "number out of range".to_string(),
                 ));
             }
         }
@@ -0,0 +1,325 @@
+use std::{
+    cell::RefCell,
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ast::stmt::Stmt,
+    coverage::{Coverage, CoverageCollector, FileCoverage},
+    error::RloxError,
+    interpreter::Interpreter,
+    parser::Parser,
+    runner::find_files,
+    scanner::Scanner,
+    token::LiteralType,
+};
+
+/// Suffix of lox files containing tests.
+pub const TEST_FILE_SUFFIX: &str = "_test.lox";
+
+/// Output format of a test run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Summary,
+    Tap,
+    JUnit,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "summary" => Ok(ReportFormat::Summary),
+            "tap" => Ok(ReportFormat::Tap),
+            "junit" => Ok(ReportFormat::JUnit),
+            _ => Err(format!("Unknown report format: {s}.")),
+        }
+    }
+}
+
+/// Result of a single test block.
+#[derive(Debug)]
+pub struct TestResult {
+    pub file: String,
+    pub name: String,
+    /// Error message if the test failed.
+    pub failure: Option<String>,
+    /// What the setup and the test printed, kept out of the report stream.
+    pub output: String,
+    pub duration: Duration,
+}
+
+impl TestResult {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Find all test files under the given path, sorted by path.
+/// A path pointing to a file is returned as is.
+pub fn discover(path: &Path) -> io::Result<Vec<PathBuf>> {
+    find_files(path, TEST_FILE_SUFFIX)
+}
+
+/// Run every test block of a lox source.
+///
+/// Each test runs in a fresh `Interpreter` which first executes the top level
+/// statements of the file, so they act as a shared setup.
+pub fn run_source(file: &str, source: &str) -> Vec<TestResult> {
+    run_source_with_coverage(file, source, None)
+}
+
+/// Run every test block of a lox source, recording coverage of the file when given.
+pub fn run_source_with_coverage(
+    file: &str,
+    source: &str,
+    coverage: Option<Rc<RefCell<FileCoverage>>>,
+) -> Vec<TestResult> {
+    let start = Instant::now();
+    let load_failure = |message: String| {
+        vec![TestResult {
+            file: file.to_owned(),
+            name: "<load>".to_owned(),
+            failure: Some(message),
+            output: String::new(),
+            duration: start.elapsed(),
+        }]
+    };
+
+    let mut scanner = Scanner::new(source.to_owned());
+    let tokens = match scanner.scan_tokens() {
+        Ok(tokens) => tokens,
+        Err(e) => return load_failure(e.to_string()),
+    };
+    let mut parser = Parser::new(tokens);
+    let program = match parser.parse() {
+        Some(program) if !parser.had_error => program,
+        _ => return load_failure("Failed to parse test file.".to_owned()),
+    };
+
+    let Stmt::Program(declarations) = &program else {
+        return load_failure("Input is not a valid program!".to_owned());
+    };
+    // Register lines of the file even if it has no test to run.
+    let collector = coverage.map(|coverage| CoverageCollector::new(&program, coverage));
+    declarations
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Test(name, body) => Some((name, body)),
+            _ => None,
+        })
+        .map(|(name, body)| {
+            let start = Instant::now();
+            let mut interpreter = Interpreter::new();
+            if let Some(collector) = &collector {
+                interpreter.set_hook(Box::new(collector.clone()));
+            }
+            let output = Capture::default();
+            interpreter.set_output(Box::new(output.clone()));
+            let failure = interpreter
+                .execute(&program)
+                .and_then(|_| interpreter.execute(body))
+                .err()
+                .map(|e| e.to_string());
+            let name = match &name.literal {
+                LiteralType::String(s) => s.clone(),
+                _ => name.lexeme.clone(),
+            };
+            TestResult {
+                file: file.to_owned(),
+                name,
+                failure,
+                output: String::from_utf8_lossy(&output.0.borrow()).into_owned(),
+                duration: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// Writer keeping what a test prints, as reports go to stdout too.
+#[derive(Clone, Default)]
+struct Capture(Rc<RefCell<Vec<u8>>>);
+
+impl io::Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run all test files found under `paths` and print a report to stdout,
+/// recording coverage of the test files when given.
+/// Return whether all tests passed.
+pub fn run_tests(
+    paths: &[String],
+    format: ReportFormat,
+    mut coverage: Option<&mut Coverage>,
+) -> Result<bool, RloxError> {
+    let mut results = vec![];
+    for path in paths {
+        for file in discover(Path::new(path))? {
+            let source = fs::read_to_string(&file)?;
+            let file = file.to_string_lossy();
+            let file_coverage = coverage.as_mut().map(|coverage| coverage.file(&file));
+            results.extend(run_source_with_coverage(&file, &source, file_coverage));
+        }
+    }
+    print!("{}", render(&results, format));
+
+    Ok(results.iter().all(TestResult::passed))
+}
+
+/// Render test results in the given format.
+pub fn render(results: &[TestResult], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Summary => render_summary(results),
+        ReportFormat::Tap => render_tap(results),
+        ReportFormat::JUnit => render_junit(results),
+    }
+}
+
+fn render_summary(results: &[TestResult]) -> String {
+    let mut s = String::new();
+    for result in results {
+        match &result.failure {
+            None => writeln!(s, "PASS {} > {}", result.file, result.name),
+            Some(message) => writeln!(s, "FAIL {} > {}: {}", result.file, result.name, message),
+        }
+        .unwrap();
+        if !result.passed() {
+            for line in result.output.lines() {
+                writeln!(s, "    {line}").unwrap();
+            }
+        }
+    }
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    writeln!(
+        s,
+        "\n{} tests, {} passed, {} failed",
+        results.len(),
+        results.len() - failed,
+        failed
+    )
+    .unwrap();
+
+    s
+}
+
+fn render_tap(results: &[TestResult]) -> String {
+    let mut s = String::new();
+    writeln!(s, "TAP version 13").unwrap();
+    writeln!(s, "1..{}", results.len()).unwrap();
+    for (i, result) in results.iter().enumerate() {
+        let status = if result.passed() { "ok" } else { "not ok" };
+        writeln!(
+            s,
+            "{} {} - {} > {}",
+            status,
+            i + 1,
+            result.file,
+            result.name
+        )
+        .unwrap();
+        if let Some(message) = &result.failure {
+            writeln!(s, "  ---").unwrap();
+            writeln!(s, "  message: {:?}", message).unwrap();
+            if !result.output.is_empty() {
+                writeln!(s, "  output: {:?}", result.output).unwrap();
+            }
+            writeln!(s, "  ...").unwrap();
+        }
+    }
+
+    s
+}
+
+fn render_junit(results: &[TestResult]) -> String {
+    let mut s = String::new();
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    writeln!(s, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        s,
+        r#"<testsuites name="rlox" tests="{}" failures="{}">"#,
+        results.len(),
+        failed
+    )
+    .unwrap();
+
+    // Group results by file, keeping the order of discovery.
+    let mut files: Vec<&str> = vec![];
+    for result in results {
+        if !files.contains(&result.file.as_str()) {
+            files.push(&result.file);
+        }
+    }
+    for file in files {
+        let suite: Vec<&TestResult> = results.iter().filter(|r| r.file == file).collect();
+        let time: Duration = suite.iter().map(|r| r.duration).sum();
+        writeln!(
+            s,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.6}">"#,
+            escape_xml(file),
+            suite.len(),
+            suite.iter().filter(|r| !r.passed()).count(),
+            time.as_secs_f64()
+        )
+        .unwrap();
+        for result in suite {
+            let attributes = format!(
+                r#"name="{}" classname="{}" time="{:.6}""#,
+                escape_xml(&result.name),
+                escape_xml(file),
+                result.duration.as_secs_f64()
+            );
+            if result.passed() && result.output.is_empty() {
+                writeln!(s, "    <testcase {}/>", attributes).unwrap();
+                continue;
+            }
+            writeln!(s, "    <testcase {}>", attributes).unwrap();
+            if let Some(message) = &result.failure {
+                writeln!(s, r#"      <failure message="{}"/>"#, escape_xml(message)).unwrap();
+            }
+            if !result.output.is_empty() {
+                writeln!(
+                    s,
+                    "      <system-out>{}</system-out>",
+                    escape_xml(&result.output)
+                )
+                .unwrap();
+            }
+            writeln!(s, "    </testcase>").unwrap();
+        }
+        writeln!(s, "  </testsuite>").unwrap();
+    }
+    writeln!(s, "</testsuites>").unwrap();
+
+    s
+}
+
+/// Escape special characters in XML attribute values.
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -1,10 +1,15 @@
+use crate::bignum::{BigInt, Decimal};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum TokenType {
     // Single-character tokens.
     LeftParen,
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
     Dot,
     Minus,
@@ -12,6 +17,12 @@ pub enum TokenType {
     Semicolon,
     Slash,
     Star,
+    Percent,
+    /// `&`, `|`, `^` and `~` bitwise operators.
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
 
     // One or two character tokens.
     Bang,
@@ -22,6 +33,25 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    /// `=>` of an arrow function.
+    Arrow,
+    /// `..` of a range.
+    DotDot,
+    /// `**` exponentiation.
+    StarStar,
+    /// `~/` integer division, `//` starting a comment.
+    TildeSlash,
+    /// `+=`, `-=`, `*=` and `/=` compound assignments.
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    /// `++` and `--` increment and decrement.
+    PlusPlus,
+    MinusMinus,
+    /// `<<` and `>>` shifts.
+    LessLess,
+    GreaterGreater,
 
     // Ternary
     QuestionMark,
@@ -34,12 +64,15 @@ pub enum TokenType {
 
     // Keywords.
     And,
+    Break,
     Class,
+    Continue,
     Else,
     False,
     Fun,
     For,
     If,
+    In,
     Nil,
     Or,
     Print,
@@ -53,17 +86,34 @@ pub enum TokenType {
     Eof,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token {
     pub token_type: TokenType,
     pub lexeme: String,
     pub literal: LiteralType,
     pub line: usize,
+    pub span: Span,
+}
+
+/// Byte range of a token in source code.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum LiteralType {
     String(String),
+    /// Number written without a fractional part.
+    Int(i64),
+    /// Number written with a `n` suffix.
+    BigInt(BigInt),
+    /// Number written with a `d` suffix.
+    Decimal(Decimal),
     Number(f64),
     Bool(bool),
     Nil,
@@ -76,6 +126,7 @@ impl Token {
             lexeme,
             literal,
             line,
+            span: Span::default(),
         }
     }
 }
@@ -0,0 +1,70 @@
+use std::{io::Write, ops::RangeInclusive};
+
+use crate::{
+    ast::{expr::Expr, pretty_printer::AstPrinter, stmt::Stmt},
+    environment::Environment,
+    error::RloxError,
+    interpreter::Hook,
+    value::LoxValue,
+};
+
+/// Hook logging every executed statement and evaluated expression.
+///
+/// Each record carries the source line and the block nesting depth, e.g.
+/// `[line 3] [depth 1] expr (+ a 1) => 2`.
+pub struct Tracer {
+    output: Box<dyn Write>,
+    /// Only lines in this range are traced, when given.
+    lines: Option<RangeInclusive<usize>>,
+    /// Line of the statement being executed, used for expressions without a line.
+    line: usize,
+}
+
+impl Tracer {
+    pub fn new(output: Box<dyn Write>, lines: Option<RangeInclusive<usize>>) -> Self {
+        Self {
+            output,
+            lines,
+            line: 0,
+        }
+    }
+
+    fn record(&mut self, line: usize, depth: usize, text: &str) -> Result<(), RloxError> {
+        if self
+            .lines
+            .as_ref()
+            .is_none_or(|lines| lines.contains(&line))
+        {
+            writeln!(self.output, "[line {line}] [depth {depth}] {text}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Hook for Tracer {
+    fn before_stmt(
+        &mut self,
+        stmt: &Stmt,
+        environment: &Environment,
+        _calls: usize,
+    ) -> Result<(), RloxError> {
+        self.line = stmt.line();
+        let text = match stmt {
+            // Statements of a block are traced on their own.
+            Stmt::Block(..) => "block".to_owned(),
+            stmt => AstPrinter().fmt_stmt(stmt),
+        };
+        self.record(self.line, environment.depth(), &format!("stmt {text}"))
+    }
+
+    fn after_expr(
+        &mut self,
+        expr: &Expr,
+        value: &LoxValue,
+        environment: &Environment,
+    ) -> Result<(), RloxError> {
+        let line = expr.line().unwrap_or(self.line);
+        let text = format!("expr {} => {}", AstPrinter().fmt(expr), value.repr());
+        self.record(line, environment.depth(), &text)
+    }
+}
@@ -1,12 +1,179 @@
-use std::fmt::Display;
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+use crate::{
+    ast::{format_float, quote, stmt::Stmt},
+    bignum::{BigInt, Decimal},
+    environment::Environment,
+    error::RloxError,
+    native::NativeFunction,
+    token::Token,
+};
+
+/// List shared by every value referring to it.
+pub type List = Rc<RefCell<Vec<LoxValue>>>;
+
+/// Map shared by every value referring to it.
+pub type MapRef = Rc<RefCell<Map>>;
+
+/// Value usable as a map key.
+///
+/// Floats are keyed by their bits, `-0` being the same key as `0`, and integral
+/// floats, big integers and decimals by the integer they equal, as `1 == 1.0`.
+/// A decimal that is exactly a float is keyed as that float, as `0.5d == 0.5`.
+/// NaN is rejected since it is not equal to itself and could never be looked up.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    String(String),
+    Int(i64),
+    BigInt(BigInt),
+    Decimal(Decimal),
+    Number(u64),
+    Bool(bool),
+    Nil,
+}
+
+impl MapKey {
+    pub fn to_value(&self) -> LoxValue {
+        match self {
+            MapKey::String(s) => LoxValue::String(s.clone()),
+            MapKey::Int(n) => LoxValue::Int(*n),
+            MapKey::BigInt(n) => LoxValue::BigInt(n.clone()),
+            MapKey::Decimal(n) => LoxValue::Decimal(n.clone()),
+            MapKey::Number(bits) => LoxValue::Number(f64::from_bits(*bits)),
+            MapKey::Bool(b) => LoxValue::Bool(*b),
+            MapKey::Nil => LoxValue::Nil,
+        }
+    }
+}
+
+impl TryFrom<&LoxValue> for MapKey {
+    type Error = RloxError;
+
+    fn try_from(value: &LoxValue) -> Result<Self, Self::Error> {
+        match value {
+            LoxValue::String(s) => Ok(MapKey::String(s.clone())),
+            LoxValue::Int(n) => Ok(MapKey::Int(*n)),
+            LoxValue::BigInt(n) => Ok(n.to_i64().map_or(MapKey::BigInt(n.clone()), MapKey::Int)),
+            LoxValue::Decimal(n) if n.is_integer() => {
+                MapKey::try_from(&LoxValue::BigInt(n.trunc()))
+            }
+            LoxValue::Decimal(n) if Decimal::from_f64_exact(n.to_f64()).as_ref() == Some(n) => {
+                Ok(MapKey::Number(n.to_f64().to_bits()))
+            }
+            LoxValue::Decimal(n) => Ok(MapKey::Decimal(n.clone())),
+            LoxValue::Number(n) if n.is_nan() => {
+                Err(RloxError::RuntimeError("Map key can't be NaN.".to_owned()))
+            }
+            LoxValue::Number(n) if let Some(n) = float_to_int(*n) => Ok(MapKey::Int(n)),
+            LoxValue::Number(n)
+                if let Some(n) = Decimal::from_f64_exact(*n)
+                    && n.is_integer() =>
+            {
+                Ok(MapKey::BigInt(n.trunc()))
+            }
+            // Adding zero turns -0 into 0.
+            LoxValue::Number(n) => Ok(MapKey::Number((n + 0.0).to_bits())),
+            LoxValue::Bool(b) => Ok(MapKey::Bool(*b)),
+            LoxValue::Nil => Ok(MapKey::Nil),
+            value => Err(RloxError::RuntimeError(format!(
+                "Map key must be a string, number, bool or nil, got {}.",
+                value.type_name()
+            ))),
+        }
+    }
+}
+
+/// Entries of a map, iterated in insertion order.
+#[derive(Debug, Default)]
+pub struct Map {
+    entries: Vec<(MapKey, LoxValue)>,
+    /// Position of each key in `entries`.
+    positions: HashMap<MapKey, usize>,
+}
+
+impl Map {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&LoxValue> {
+        self.positions.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    /// Set the value of a key, keeping its position if already present.
+    pub fn insert(&mut self, key: MapKey, value: LoxValue) {
+        match self.positions.get(&key) {
+            Some(&i) => self.entries[i].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    /// Remove a key, returning its value if it was present.
+    pub fn remove(&mut self, key: &MapKey) -> Option<LoxValue> {
+        let i = self.positions.remove(key)?;
+        let (_, value) = self.entries.remove(i);
+        for (key, _) in &self.entries[i..] {
+            *self.positions.get_mut(key).unwrap() -= 1;
+        }
+        Some(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&MapKey, &LoxValue)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+}
+
+/// Function created by a lambda, closing over the scope it was created in.
+pub struct LoxFunction {
+    pub params: Vec<Token>,
+    /// Block of a `fun` lambda, or `return` statement of an arrow function.
+    pub body: Rc<Stmt>,
+    pub closure: Environment,
+}
+
+impl fmt::Debug for LoxFunction {
+    // The closure may hold the function itself, so it is left out.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoxFunction")
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
 
 /// Lox builtin value types.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub enum LoxValue {
     String(String),
+    Int(i64),
+    /// Integer of any size, written with a `n` suffix such as `10n`.
+    BigInt(BigInt),
+    /// Exact decimal, written with a `d` suffix such as `1.10d`.
+    Decimal(Decimal),
+    /// Float, produced by literals with a fractional part and mixed arithmetic.
     Number(f64),
     Bool(bool),
     Nil,
+    NativeFunction(Rc<NativeFunction>),
+    Function(Rc<LoxFunction>),
+    List(List),
+    Map(MapRef),
+    /// Integers from a start, by steps of one, up to an excluded end.
+    IntRange(i64, i64),
+    /// Floats from a start, by steps of one, up to an excluded end.
+    Range(f64, f64),
 }
 
 impl LoxValue {
@@ -17,15 +184,203 @@ impl LoxValue {
             _ => true,
         }
     }
-}
 
-impl Display for LoxValue {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    /// Create a list holding the given values.
+    pub fn list(values: Vec<LoxValue>) -> Self {
+        LoxValue::List(Rc::new(RefCell::new(values)))
+    }
+
+    /// Create a map holding the given entries.
+    pub fn map(map: Map) -> Self {
+        LoxValue::Map(Rc::new(RefCell::new(map)))
+    }
+
+    /// Name of the type of the value, as returned by `type()`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            LoxValue::String(_) => "string",
+            LoxValue::Int(_) => "int",
+            LoxValue::BigInt(_) => "bigint",
+            LoxValue::Decimal(_) => "decimal",
+            LoxValue::Number(_) => "float",
+            LoxValue::Bool(_) => "bool",
+            LoxValue::Nil => "nil",
+            LoxValue::NativeFunction(_) | LoxValue::Function(_) => "function",
+            LoxValue::List(_) => "list",
+            LoxValue::Map(_) => "map",
+            LoxValue::IntRange(..) | LoxValue::Range(..) => "range",
+        }
+    }
+
+    /// Value of a number as a float.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            LoxValue::Int(n) => Some(*n as f64),
+            LoxValue::BigInt(n) => Some(n.to_f64()),
+            LoxValue::Decimal(n) => Some(n.to_f64()),
+            LoxValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Value of an int or big integer as a big integer.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            LoxValue::Int(n) => Some(BigInt::from(*n)),
+            LoxValue::BigInt(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+
+    /// Exact value of an int, big integer or decimal as a decimal.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            LoxValue::Decimal(n) => Some(n.clone()),
+            value => value.to_bigint().map(Decimal::from),
+        }
+    }
+
+    /// Exact value of a finite number as a decimal, floats included.
+    pub fn to_exact_decimal(&self) -> Option<Decimal> {
+        match self {
+            LoxValue::Number(n) => Decimal::from_f64_exact(*n),
+            value => value.to_decimal(),
+        }
+    }
+
+    /// Order two numbers by their exact values, never rounding a big integer or
+    /// decimal to a float. `None` if either isn't a number or is NaN.
+    pub fn exact_cmp(&self, other: &Self) -> Option<Ordering> {
+        let (a, b) = (self.to_f64()?, other.to_f64()?);
+        // Rounding to a float keeps the order, so only ties need exact values.
+        match a.partial_cmp(&b)? {
+            Ordering::Equal => match (self.to_exact_decimal(), other.to_exact_decimal()) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                // An infinite float tied with a number too large for a float.
+                (Some(_), None) => Some(if b > 0.0 {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }),
+                (None, Some(_)) => Some(if a > 0.0 {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }),
+                (None, None) => Some(Ordering::Equal),
+            },
+            ordering => Some(ordering),
+        }
+    }
+
+    /// Format the value as written in source, quoting strings.
+    pub fn repr(&self) -> String {
+        match self {
+            LoxValue::String(s) => quote(s),
+            value => value.to_string(),
+        }
+    }
+
+    /// Write the value, quoting strings inside collections and eliding
+    /// collections containing themselves, whose addresses are in `seen`.
+    fn write(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        nested: bool,
+        seen: &mut Vec<*const ()>,
+    ) -> fmt::Result {
         match self {
             LoxValue::Nil => write!(f, "nil"),
             LoxValue::Bool(b) => write!(f, "{}", b),
-            LoxValue::Number(num) => write!(f, "{}", num),
+            LoxValue::Int(n) => write!(f, "{}", n),
+            LoxValue::BigInt(n) => write!(f, "{}", n),
+            LoxValue::Decimal(n) => write!(f, "{}", n),
+            LoxValue::Number(num) => write!(f, "{}", format_float(*num)),
+            LoxValue::String(s) if nested => write!(f, "{}", quote(s)),
             LoxValue::String(s) => write!(f, "{}", s),
+            LoxValue::NativeFunction(native) => write!(f, "<native fn {}>", native.name),
+            LoxValue::Function(_) => write!(f, "<fn>"),
+            LoxValue::IntRange(start, end) => write!(f, "{start}..{end}"),
+            LoxValue::Range(start, end) => {
+                write!(f, "{}..{}", format_float(*start), format_float(*end))
+            }
+            LoxValue::List(list) => {
+                let address = Rc::as_ptr(list) as *const ();
+                if seen.contains(&address) {
+                    return write!(f, "[...]");
+                }
+                seen.push(address);
+                write!(f, "[")?;
+                for (i, value) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    value.write(f, true, seen)?;
+                }
+                seen.pop();
+                write!(f, "]")
+            }
+            LoxValue::Map(map) => {
+                let address = Rc::as_ptr(map) as *const ();
+                if seen.contains(&address) {
+                    return write!(f, "{{...}}");
+                }
+                seen.push(address);
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    key.to_value().write(f, true, seen)?;
+                    write!(f, ": ")?;
+                    value.write(f, true, seen)?;
+                }
+                seen.pop();
+                write!(f, "}}")
+            }
         }
     }
 }
+
+impl PartialEq for LoxValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (LoxValue::String(a), LoxValue::String(b)) => a == b,
+            (LoxValue::Int(a), LoxValue::Int(b)) => a == b,
+            (LoxValue::Number(a), LoxValue::Number(b)) => a == b,
+            (LoxValue::Int(a), LoxValue::Number(b)) | (LoxValue::Number(b), LoxValue::Int(a)) => {
+                float_to_int(*b) == Some(*a)
+            }
+            (LoxValue::BigInt(_) | LoxValue::Decimal(_), _)
+            | (_, LoxValue::BigInt(_) | LoxValue::Decimal(_)) => {
+                match (self.to_decimal(), other.to_decimal()) {
+                    (Some(a), Some(b)) => a == b,
+                    // Compared with a float, or not a number.
+                    _ => self.exact_cmp(other) == Some(Ordering::Equal),
+                }
+            }
+            (LoxValue::Bool(a), LoxValue::Bool(b)) => a == b,
+            (LoxValue::Nil, LoxValue::Nil) => true,
+            (LoxValue::NativeFunction(a), LoxValue::NativeFunction(b)) => a == b,
+            (LoxValue::Function(a), LoxValue::Function(b)) => Rc::ptr_eq(a, b),
+            // A collection is only equal to itself, as seen through any alias.
+            (LoxValue::List(a), LoxValue::List(b)) => Rc::ptr_eq(a, b),
+            (LoxValue::Map(a), LoxValue::Map(b)) => Rc::ptr_eq(a, b),
+            (LoxValue::IntRange(a, b), LoxValue::IntRange(c, d)) => a == c && b == d,
+            (LoxValue::Range(a, b), LoxValue::Range(c, d)) => a == c && b == d,
+            _ => false,
+        }
+    }
+}
+
+/// The integer a float equals, if it is integral and within 64 bits.
+pub fn float_to_int(n: f64) -> Option<i64> {
+    // `i64::MIN` is a power of two, so both bounds are exact floats.
+    (n.fract() == 0.0 && n >= i64::MIN as f64 && n < -(i64::MIN as f64)).then_some(n as i64)
+}
+
+impl Display for LoxValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, false, &mut vec![])
+    }
+}
@@ -0,0 +1,122 @@
+use rlox::{
+    ast::{pretty_printer::AstPrinter, reader::AstReader},
+    runner::format_source,
+};
+
+mod common;
+use common::{parse, run, syntax_error};
+
+#[test]
+fn test_compound_assignment() {
+    let source = "var a = 1;
+a += 2;
+print a;
+a -= 1;
+print a;
+a *= 6;
+print a;
+print a /= 4;
+var s = \"ab\";
+s += \"c\";
+print s;
+var b = a += 1;
+print b;";
+    assert_eq!(run(source), Ok("3\n2\n12\n3.0\nabc\n4.0\n".to_owned()));
+}
+
+#[test]
+fn test_increment_and_decrement() {
+    let source = "var i = 0;
+print i++;
+print i;
+print ++i;
+print i--;
+print --i;
+print i;
+var n = 0;
+while (n < 3) n++;
+print n;";
+    assert_eq!(run(source), Ok("0\n1\n2\n2\n0\n0\n3\n".to_owned()));
+}
+
+#[test]
+fn test_index_targets() {
+    // The object and index of a target are evaluated once.
+    let source = "var xs = [1, 2, 3];
+var i = 0;
+xs[i++] += 10;
+print xs;
+print i;
+xs[i]--;
+print ++xs[2];
+print xs;
+var m = {\"count\": 0};
+m[\"count\"]++;
+m[\"count\"] *= 5;
+print m;";
+    assert_eq!(
+        run(source),
+        Ok("[11, 2, 3]\n1\n4\n[11, 1, 4]\n{\"count\": 5}\n".to_owned())
+    );
+}
+
+#[test]
+fn test_runtime_errors() {
+    let cases = [
+        ("var s = \"a\"; s++;", "Operand of '++' must be a number."),
+        ("var s = nil; --s;", "Operand of '--' must be a number."),
+        ("var s = \"a\"; s -= 1;", "Operands must be two numbers."),
+        ("var n = 1; n /= 0;", "can not divided by zero"),
+        ("undefined += 1;", "Undefined variable: undefined."),
+        ("var m = {}; m[\"k\"]++;", "Undefined key \"k\"."),
+        (
+            "var xs = []; xs[0] += 1;",
+            "Index 0 out of bounds for list of length 0.",
+        ),
+    ];
+    for (source, message) in cases {
+        assert_eq!(
+            run(source),
+            Err(format!("Runtime error: {message}")),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn test_invalid_targets() {
+    assert_eq!(syntax_error("1 += 2;"), "Invalid assignment target: +=");
+    assert_eq!(
+        syntax_error("var a; (a) *= 2;"),
+        "Invalid assignment target: *="
+    );
+    assert_eq!(
+        syntax_error("var a; a + 1 -= 2;"),
+        "Invalid assignment target: -="
+    );
+    assert_eq!(syntax_error("1++;"), "Invalid assignment target: ++");
+    assert_eq!(syntax_error("--f();"), "Invalid assignment target: --");
+    assert!(AstReader::read_expr("(+= 1 2)").is_err());
+    assert!(AstReader::read_expr("(post++ (call f))").is_err());
+}
+
+#[test]
+fn test_printer_and_reader() {
+    let source = "a += 1; xs[0] /= --b; print c++;";
+    let program = parse(source).parse().unwrap();
+    let printed = AstPrinter().fmt_stmt(&program);
+    assert_eq!(
+        printed,
+        "[(+= a 1);(/= (index xs 0) (-- b));(print (post++ c))]"
+    );
+    let read = AstReader::read_program(&printed).unwrap();
+    assert_eq!(AstPrinter().fmt_stmt(&read), printed);
+}
+
+#[test]
+fn test_formatter() {
+    assert_eq!(
+        format_source("a+=1;xs[i++]*=(b-=2);print -(--c);print -(-c);print (d++)**2;").unwrap(),
+        "a += 1;\nxs[i++] *= b -= 2;\nprint - --c;\nprint - -c;\nprint d++ ** 2;\n"
+    );
+}
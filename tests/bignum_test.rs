@@ -0,0 +1,189 @@
+use rlox::{
+    ast::{pretty_printer::AstPrinter, reader::AstReader},
+    bignum::{BigInt, Decimal},
+    parser::Parser,
+    runner::format_source,
+    scanner::Scanner,
+    token::LiteralType,
+};
+
+mod common;
+use common::{assert_evals, assert_fails};
+
+#[test]
+fn test_literals() {
+    let tokens = Scanner::new("10n 1.10d 3d".to_owned())
+        .scan_tokens()
+        .unwrap();
+    let literals: Vec<&LiteralType> = tokens[..3].iter().map(|t| &t.literal).collect();
+    assert_eq!(
+        literals,
+        vec![
+            &LiteralType::BigInt(BigInt::from(10)),
+            &LiteralType::Decimal("1.10".parse().unwrap()),
+            &LiteralType::Decimal(Decimal::from(3)),
+        ]
+    );
+    assert!(Scanner::new("1.5n".to_owned()).scan_tokens().is_err());
+    assert_evals(&[
+        ("1.10d", "1.10"),
+        ("-0.5d", "-0.5"),
+        (
+            "123456789012345678901234567890n",
+            "123456789012345678901234567890",
+        ),
+        ("type(1n) + type(1d)", "bigintdecimal"),
+    ]);
+}
+
+#[test]
+fn test_big_integers() {
+    assert_evals(&[
+        ("9223372036854775807n + 1", "9223372036854775808"),
+        ("2n ** 100", "1267650600228229401496703205376"),
+        (
+            "99999999999999999999n * 99999999999999999999n",
+            "9999999999999999999800000000000000000001",
+        ),
+        ("-7n ~/ 2", "-4"),
+        ("-7n % 3", "2"),
+        ("7n / 2", "3.5"),
+        ("10n ** 30 > 10n ** 29", "true"),
+        ("1n == 1", "true"),
+        ("1n == 1.0", "true"),
+        ("2n ** 64 - 2n ** 64 + 5 & 4", "4"),
+    ]);
+}
+
+#[test]
+fn test_decimals() {
+    assert_evals(&[
+        ("0.1d + 0.2d", "0.3"),
+        ("0.1d + 0.2d == 0.3d", "true"),
+        ("1.10d + 2", "3.10"),
+        ("19.99d * 3", "59.97"),
+        ("1d / 3", "0.33333333333333333333"),
+        ("2d / 3", "0.66666666666666666667"),
+        ("1d / 8", "0.125"),
+        ("10.00d / 4", "2.50"),
+        ("-7.5d ~/ 2", "-4"),
+        ("-7.5d % 2", "0.5"),
+        ("1.5d ** 2", "2.25"),
+        ("1.50d == 1.5d", "true"),
+        ("1.5d < 2n", "true"),
+        ("10n ** 20 + 0.5d", "100000000000000000000.5"),
+    ]);
+}
+
+#[test]
+fn test_errors() {
+    assert_fails(&[
+        (
+            "0.1d + 0.1",
+            "Can't mix decimals and floats, convert one with decimal() or float().",
+        ),
+        ("1d / 0", "can not divided by zero"),
+        ("1n % 0", "Modulo by zero."),
+        ("1n ~/ 0n", "Integer division by zero."),
+        (
+            "2d ** 0.5d",
+            "Exponent of a decimal must be a non negative integer.",
+        ),
+        ("2n ** 10000000000", "Result of '**' is too large."),
+        ("1n..3", "Range bounds must be ints or floats."),
+        (
+            "2n ** 64 | 1",
+            "Operands of '|' must be 64-bit integers, got 18446744073709551616.",
+        ),
+        (
+            "int(2n ** 64)",
+            "int() can't convert 18446744073709551616 to an int.",
+        ),
+        (
+            "decimal(10.0 ** 400)",
+            "decimal() can't convert inf to a decimal.",
+        ),
+    ]);
+}
+
+#[test]
+fn test_natives() {
+    assert_evals(&[
+        (
+            "bigint(\"123456789012345678901234567890\")",
+            "123456789012345678901234567890",
+        ),
+        ("bigint(\"1.5\")", "nil"),
+        ("bigint(7)", "7"),
+        ("bigint(-2.9)", "-2"),
+        ("bigint(-2.9d)", "-2"),
+        ("decimal(\"12.50\")", "12.50"),
+        ("decimal(\"abc\")", "nil"),
+        ("decimal(0.1)", "0.1"),
+        ("decimal(5n)", "5"),
+        ("float(1.25d)", "1.25"),
+        ("int(12.9d)", "12"),
+        ("int(5n)", "5"),
+        ("str(1.10d) + str(2n)", "1.102"),
+        ("num(2n)", "2"),
+        ("floor(-1.5d)", "-2"),
+        ("ceil(-1.5d)", "-1"),
+        ("abs(-1.5d)", "1.5"),
+        ("abs(-(2n ** 70))", "1180591620717411303424"),
+        ("min(3n, 1.5d, 2)", "1.5"),
+        ("sqrt(4n)", "2.0"),
+    ]);
+}
+
+#[test]
+fn test_map_keys() {
+    assert_evals(&[
+        ("{1n: \"a\"}[1]", "a"),
+        ("{1.0d: \"a\"}[1]", "a"),
+        ("{1.50d: \"a\"}[1.5d]", "a"),
+        ("{2n ** 70: \"a\"}[2n ** 70]", "a"),
+    ]);
+}
+
+#[test]
+fn test_floats_compare_exactly() {
+    assert_evals(&[
+        ("9007199254740993n == 9007199254740992.0", "false"),
+        ("9007199254740993n == 9007199254740993", "true"),
+        ("9007199254740993 == 9007199254740992.0", "false"),
+        ("9007199254740993n > 9007199254740992.0", "true"),
+        ("9007199254740993 > 9007199254740992.0", "true"),
+        ("2n ** 70 == 2.0 ** 70", "true"),
+        ("2n ** 70 + 1 > 2.0 ** 70", "true"),
+        ("10n ** 400 < 10.0 ** 400", "true"),
+        ("-(10n ** 400) > -(10.0 ** 400)", "true"),
+        ("1n < 10.0 ** 400 - 10.0 ** 400", "false"),
+        ("1n >= 10.0 ** 400 - 10.0 ** 400", "false"),
+        ("0.5d == 0.5", "true"),
+        ("0.5d < 0.6", "true"),
+        ("0.5d >= 0.5", "true"),
+        ("0.1d == 0.1", "false"),
+        ("0.1d < 0.1", "true"),
+        ("1.0d == 1.0", "true"),
+        ("{0.5d: \"a\"}[0.5]", "a"),
+        ("len({0.5d: 1, 0.5: 2})", "1"),
+        ("len({0.1d: 1, 0.1: 2})", "2"),
+        ("{2n ** 70: \"a\"}[2.0 ** 70]", "a"),
+    ]);
+}
+
+#[test]
+fn test_round_trips() {
+    assert_eq!(
+        format_source("print 1.10d+2n;").unwrap(),
+        "print 1.10d + 2n;\n"
+    );
+    let tokens = Scanner::new("1.10d * 2n".to_owned()).scan_tokens().unwrap();
+    let expr = Parser::new(tokens).parse_expr().unwrap();
+    let printed = AstPrinter().fmt(&expr);
+    assert_eq!(printed, "(* 1.10d 2n)");
+    assert_eq!(
+        AstPrinter().fmt(&AstReader::read_expr(&printed).unwrap()),
+        printed
+    );
+}
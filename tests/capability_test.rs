@@ -0,0 +1,110 @@
+use std::{fs, process::Command};
+
+use rlox::{
+    interpreter::Interpreter,
+    native::{Capabilities, Capability},
+};
+use tempfile::tempdir;
+
+mod common;
+use common::run_in;
+
+#[test]
+fn test_capability_sets() {
+    let none = Capabilities::none();
+    assert!(Capability::ALL.iter().all(|&c| !none.allows(c)));
+    let all = Capabilities::all();
+    assert!(Capability::ALL.iter().all(|&c| all.allows(c)));
+
+    let env_only = none.with(Capability::Env);
+    assert!(env_only.allows(Capability::Env));
+    assert!(!env_only.allows(Capability::Fs));
+    assert!(!all.without(Capability::Process).allows(Capability::Process));
+
+    assert_eq!("time".parse(), Ok(Capability::Time));
+    assert!("network".parse::<Capability>().is_err());
+}
+
+#[test]
+fn test_denied_capabilities() {
+    let cases = [
+        (
+            "clock();",
+            "clock() requires the 'time' capability, which is denied.",
+        ),
+        (
+            "getenv(\"HOME\");",
+            "getenv() requires the 'env' capability, which is denied.",
+        ),
+        (
+            "exit(1);",
+            "exit() requires the 'process' capability, which is denied.",
+        ),
+    ];
+    for (source, message) in cases {
+        let mut interpreter = Interpreter::with_capabilities(Capabilities::none());
+        assert_eq!(
+            run_in(&mut interpreter, source),
+            Err(format!("Runtime error: {message}"))
+        );
+    }
+}
+
+#[test]
+fn test_natives_without_capability_always_allowed() {
+    let mut interpreter = Interpreter::with_capabilities(Capabilities::none());
+    assert_eq!(run_in(&mut interpreter, "assert(true);"), Ok(()));
+}
+
+#[test]
+fn test_granted_capabilities() {
+    let capabilities = Capabilities::none()
+        .with(Capability::Time)
+        .with(Capability::Env);
+    let mut interpreter = Interpreter::with_capabilities(capabilities);
+    let source = "assert(clock() > 0); assert(getenv(\"RLOX_SURELY_UNSET\") == nil);";
+    assert_eq!(run_in(&mut interpreter, source), Ok(()));
+    assert_eq!(
+        run_in(&mut interpreter, "exit();"),
+        Err("Runtime error: exit() requires the 'process' capability, which is denied.".to_owned())
+    );
+}
+
+#[test]
+fn test_cli_grants_all_capabilities() {
+    let dir = tempdir().unwrap();
+    let script = dir.path().join("exit.lox");
+    fs::write(
+        &script,
+        "print getenv(\"RLOX_TEST_VALUE\");\nexit(3);\nprint 2;\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .arg(&script)
+        .env("RLOX_TEST_VALUE", "granted")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "granted\n");
+}
+
+#[test]
+fn test_exit_returns_to_the_host() {
+    let mut interpreter = Interpreter::new();
+    assert_eq!(
+        run_in(&mut interpreter, "exit(3); print 1;"),
+        Err("Exited with status 3.".to_owned())
+    );
+    assert_eq!(
+        run_in(&mut interpreter, "exit();"),
+        Err("Exited with status 0.".to_owned())
+    );
+    for status in ["4294967296", "256", "-1"] {
+        assert_eq!(
+            run_in(&mut interpreter, &format!("exit({status});")),
+            Err(format!(
+                "Runtime error: exit() status must be between 0 and 255, got {status}."
+            ))
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Helpers shared by the integration tests, each test crate using a subset.
+#![allow(dead_code)]
+
+use std::{cell::RefCell, io, rc::Rc};
+
+use rlox::{error::RloxError, interpreter::Interpreter, parser::Parser, scanner::Scanner};
+
+/// Writer whose content stays readable after being handed to the interpreter.
+#[derive(Clone, Default)]
+pub struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Buffer {
+    /// Take what was written so far.
+    pub fn take(&self) -> String {
+        String::from_utf8(self.0.take()).unwrap()
+    }
+}
+
+pub fn parse(source: &str) -> Parser {
+    let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+    Parser::new(tokens)
+}
+
+/// Run a program in an interpreter, returning its runtime error.
+pub fn run_in(interpreter: &mut Interpreter, source: &str) -> Result<(), String> {
+    let mut parser = parse(source);
+    let program = parser.parse().unwrap();
+    assert!(!parser.had_error, "Failed to parse {source}");
+    interpreter.execute(&program).map_err(|e| e.to_string())
+}
+
+/// Run a program, returning what it printed or its runtime error.
+pub fn run(source: &str) -> Result<String, String> {
+    let buffer = Buffer::default();
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(buffer.clone()));
+    run_in(&mut interpreter, source)?;
+    Ok(buffer.take())
+}
+
+/// Print the value of an expression, or return the runtime error it raises.
+pub fn eval(expr: &str) -> Result<String, String> {
+    let output = run(&format!("print {expr};"))?;
+    Ok(output.trim_end().to_owned())
+}
+
+pub fn assert_evals(cases: &[(&str, &str)]) {
+    for (expr, expected) in cases {
+        assert_eq!(eval(expr).as_deref(), Ok(*expected), "expr: {expr}");
+    }
+}
+
+/// Check the runtime error of each expression, given without its prefix.
+pub fn assert_fails(cases: &[(&str, &str)]) {
+    for (expr, message) in cases {
+        assert_eq!(
+            eval(expr),
+            Err(format!("Runtime error: {message}")),
+            "expr: {expr}"
+        );
+    }
+}
+
+/// Check the runtime error of each program, given without its prefix.
+pub fn assert_runs_fail(cases: &[(&str, &str)]) {
+    for (source, message) in cases {
+        assert_eq!(
+            run(source),
+            Err(format!("Runtime error: {message}")),
+            "source: {source}"
+        );
+    }
+}
+
+/// Return the message of the first syntax error of a program.
+pub fn syntax_error(source: &str) -> String {
+    let mut parser = parse(source);
+    parser.parse();
+    match parser.errors.first() {
+        Some(RloxError::SyntaxError(_, _, message)) => message.clone(),
+        other => panic!("Expected a syntax error for {source}, got {other:?}"),
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fs, process::Command};
+
+use rlox::{
+    coverage::{BranchCoverage, Coverage},
+    test_runner::run_source_with_coverage,
+};
+use tempfile::tempdir;
+
+const SUITE: &str = r#"var sign = 0;
+test "positive" {
+  var n = 3;
+  sign = n > 0 ? 1 : -1;
+  assert(sign == 1);
+}
+test "zero" {
+  assert(0 == 0 ? true : false);
+}
+"#;
+
+/// A file without tests is never run.
+const UNCOVERED: &str = "var a = 1;\n{\n  print a;\n}\n";
+
+fn collect(files: &[(&str, &str)]) -> Coverage {
+    let mut coverage = Coverage::default();
+    for (path, source) in files {
+        let results = run_source_with_coverage(path, source, Some(coverage.file(path)));
+        assert!(results.iter().all(|r| r.passed()));
+    }
+    coverage
+}
+
+#[test]
+fn test_line_hits() {
+    let coverage = collect(&[("suite_test.lox", SUITE)]);
+    let file = coverage.files["suite_test.lox"].borrow();
+    let lines: Vec<(usize, u64)> = file.lines.iter().map(|(l, h)| (*l, *h)).collect();
+    // Top level statements run once per test.
+    assert_eq!(
+        lines,
+        [(1, 2), (2, 1), (3, 1), (4, 1), (5, 1), (7, 1), (8, 1)]
+    );
+}
+
+#[test]
+fn test_branch_hits() {
+    let coverage = collect(&[("suite_test.lox", SUITE)]);
+    let file = coverage.files["suite_test.lox"].borrow();
+    assert_eq!(
+        file.branches,
+        [
+            BranchCoverage {
+                line: 4,
+                taken: [1, 0],
+            },
+            BranchCoverage {
+                line: 8,
+                taken: [1, 0],
+            },
+        ]
+    );
+    assert_eq!(file.branches_hit(), 2);
+    assert_eq!(file.branches_found(), 4);
+}
+
+#[test]
+fn test_if_branch_hits() {
+    let source = "test \"loop\" {\n  var i = 0;\n  while (i < 3) {\n    if (i == 1) print i;\n    i = i + 1;\n  }\n}\n";
+    let coverage = collect(&[("if_test.lox", source)]);
+    let file = coverage.files["if_test.lox"].borrow();
+    assert_eq!(
+        file.branches,
+        [BranchCoverage {
+            line: 4,
+            taken: [1, 2],
+        }]
+    );
+}
+
+#[test]
+fn test_uncovered_lines() {
+    let coverage = collect(&[("uncovered_test.lox", UNCOVERED)]);
+    let file = coverage.files["uncovered_test.lox"].borrow();
+    let lines: Vec<(usize, u64)> = file.lines.iter().map(|(l, h)| (*l, *h)).collect();
+    assert_eq!(lines, [(1, 0), (2, 0), (3, 0)]);
+    drop(file);
+    assert_eq!(coverage.line_percent(), 0.0);
+}
+
+#[test]
+fn test_lcov() {
+    let coverage = collect(&[("suite_test.lox", SUITE)]);
+    assert_eq!(
+        coverage.lcov(),
+        "TN:
+SF:suite_test.lox
+DA:1,2
+DA:2,1
+DA:3,1
+DA:4,1
+DA:5,1
+DA:7,1
+DA:8,1
+BRDA:4,0,0,1
+BRDA:4,0,1,0
+BRDA:8,1,0,1
+BRDA:8,1,1,0
+BRF:4
+BRH:2
+LF:7
+LH:7
+end_of_record
+"
+    );
+}
+
+#[test]
+fn test_unreached_branch_in_lcov() {
+    let source = "test \"t\" {\n  var a = 1;\n}\nvar b = a ? 1 : 2;\n";
+    let mut coverage = Coverage::default();
+    let file = coverage.file("t_test.lox");
+    // The test fails, `a` is only defined inside it.
+    run_source_with_coverage("t_test.lox", source, Some(file));
+    assert!(coverage.lcov().contains("BRDA:4,0,0,-\nBRDA:4,0,1,-\n"));
+    assert!(coverage.line_percent() < 100.0);
+}
+
+#[test]
+fn test_summary() {
+    let coverage = collect(&[("a_test.lox", SUITE), ("b_test.lox", UNCOVERED)]);
+    assert_eq!(
+        coverage.summary(),
+        "Coverage:
+  a_test.lox: lines 7/7 (100.0%), branches 2/4 (50.0%)
+  b_test.lox: lines 0/3 (0.0%), branches 0/0 (100.0%)
+  total: lines 7/10 (70.0%), branches 2/4 (50.0%)
+"
+    );
+}
+
+#[test]
+fn test_coverage_cli() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("lib_test.lox"),
+        "test \"t\" {\n  assert(true);\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("helper_test.lox"), "var never = 1;\n").unwrap();
+    let lcov = dir.path().join("lcov.info");
+
+    let run = |fail_under: &str| {
+        Command::new(env!("CARGO_BIN_EXE_rlox"))
+            .arg("test")
+            .arg(format!("--lcov={}", lcov.display()))
+            .arg(format!("--fail-under={fail_under}"))
+            .arg(dir.path())
+            .output()
+            .unwrap()
+    };
+
+    let output = run("60");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("total: lines 2/3 (66.7%), branches 0/0 (100.0%)"));
+    let lcov = fs::read_to_string(&lcov).unwrap();
+    assert!(lcov.contains("helper_test.lox\nDA:1,0\n"));
+    assert!(lcov.contains("LF:2\nLH:2\n"));
+
+    let output = run("70");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Line coverage 66.7% is below 70%."));
+}
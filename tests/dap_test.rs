@@ -0,0 +1,356 @@
+#![cfg(feature = "dap")]
+
+use std::io::{Cursor, Write};
+
+use rlox::{
+    dap::server::serve,
+    rpc::{read_message, write_message},
+};
+use serde_json::{Value, json};
+use tempfile::NamedTempFile;
+
+const SOURCE: &str = "var a = 1;
+{
+  var b = a + 1;
+  print b;
+}
+print a;
+";
+
+/// Scripted client: queues requests, runs a debug session over them and
+/// collects everything the adapter sent back.
+struct Client {
+    input: Vec<u8>,
+    seq: u64,
+    program: NamedTempFile,
+}
+
+impl Client {
+    fn new(source: &str) -> Self {
+        let mut program = NamedTempFile::new().unwrap();
+        program.write_all(source.as_bytes()).unwrap();
+        let mut client = Self {
+            input: vec![],
+            seq: 0,
+            program,
+        };
+        client.request("initialize", json!({ "adapterID": "rlox" }));
+        client
+    }
+
+    fn request(&mut self, command: &str, arguments: Value) -> u64 {
+        self.seq += 1;
+        let message = json!({
+            "seq": self.seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        });
+        write_message(&mut self.input, &message).unwrap();
+        self.seq
+    }
+
+    fn launch(&mut self, stop_on_entry: bool) -> u64 {
+        let program = self.program.path().to_string_lossy().into_owned();
+        self.request(
+            "launch",
+            json!({ "program": program, "stopOnEntry": stop_on_entry }),
+        )
+    }
+
+    fn set_breakpoints(&mut self, lines: &[u64]) -> u64 {
+        let breakpoints: Vec<Value> = lines.iter().map(|line| json!({ "line": line })).collect();
+        let path = self.program.path().to_string_lossy().into_owned();
+        self.request(
+            "setBreakpoints",
+            json!({ "source": { "path": path }, "breakpoints": breakpoints }),
+        )
+    }
+
+    fn stack_trace(&mut self) -> u64 {
+        self.request("stackTrace", json!({ "threadId": 1 }))
+    }
+
+    fn variables(&mut self, reference: u64) -> u64 {
+        self.request("variables", json!({ "variablesReference": reference }))
+    }
+
+    /// Disconnect, run the session and return every message sent by the adapter.
+    fn finish(mut self) -> Vec<Value> {
+        self.request("disconnect", json!({}));
+        let output = serve(Cursor::new(self.input), vec![]).unwrap();
+
+        let mut reader = Cursor::new(output);
+        let mut messages = vec![];
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+}
+
+fn response(messages: &[Value], seq: u64) -> &Value {
+    messages
+        .iter()
+        .find(|m| m["type"] == "response" && m["request_seq"] == seq)
+        .unwrap_or_else(|| panic!("no response for request {seq}"))
+}
+
+fn body(messages: &[Value], seq: u64) -> &Value {
+    let response = response(messages, seq);
+    assert_eq!(response["success"], true, "{response}");
+    &response["body"]
+}
+
+fn events<'a>(messages: &'a [Value], event: &'a str) -> impl Iterator<Item = &'a Value> {
+    messages
+        .iter()
+        .filter(move |m| m["type"] == "event" && m["event"] == event)
+        .map(|m| &m["body"])
+}
+
+fn stopped_reasons(messages: &[Value]) -> Vec<&str> {
+    events(messages, "stopped")
+        .map(|body| body["reason"].as_str().unwrap())
+        .collect()
+}
+
+fn frame_line(messages: &[Value], seq: u64) -> &Value {
+    &body(messages, seq)["stackFrames"][0]["line"]
+}
+
+fn variables(messages: &[Value], seq: u64) -> Vec<(String, String)> {
+    body(messages, seq)["variables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            (
+                v["name"].as_str().unwrap().to_owned(),
+                v["value"].as_str().unwrap().to_owned(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_breakpoints_and_step_over() {
+    let mut client = Client::new(SOURCE);
+    client.launch(false);
+    let breakpoints = client.set_breakpoints(&[3, 7]);
+    client.request("configurationDone", json!({}));
+    let at_breakpoint = client.stack_trace();
+    let scopes = client.request("scopes", json!({ "frameId": 0 }));
+    let locals_before = client.variables(1);
+    let globals = client.variables(2);
+    client.request("next", json!({ "threadId": 1 }));
+    let after_next = client.stack_trace();
+    let locals_after = client.variables(1);
+    client.request("next", json!({ "threadId": 1 }));
+    let out_of_block = client.stack_trace();
+    client.request("continue", json!({ "threadId": 1 }));
+    let messages = client.finish();
+
+    assert_eq!(
+        body(&messages, breakpoints)["breakpoints"],
+        json!([{ "verified": true, "line": 3 }, { "verified": false, "line": 7 }])
+    );
+    assert_eq!(stopped_reasons(&messages), ["breakpoint", "step", "step"]);
+    assert_eq!(frame_line(&messages, at_breakpoint), 3);
+    assert_eq!(
+        body(&messages, scopes)["scopes"],
+        json!([
+            { "name": "Locals", "variablesReference": 1, "expensive": false },
+            { "name": "Globals", "variablesReference": 2, "expensive": false },
+        ])
+    );
+    assert_eq!(variables(&messages, locals_before), []);
+    assert_eq!(
+        variables(&messages, globals),
+        [("a".to_owned(), "1".to_owned())]
+    );
+    assert_eq!(frame_line(&messages, after_next), 4);
+    assert_eq!(
+        variables(&messages, locals_after),
+        [("b".to_owned(), "2".to_owned())]
+    );
+    assert_eq!(frame_line(&messages, out_of_block), 6);
+
+    let output: Vec<&Value> = events(&messages, "output")
+        .map(|body| &body["output"])
+        .collect();
+    assert_eq!(output, ["2\n", "1\n"]);
+    let exited: Vec<&Value> = events(&messages, "exited").collect();
+    assert_eq!(exited, [&json!({ "exitCode": 0 })]);
+    assert_eq!(events(&messages, "terminated").count(), 1);
+}
+
+#[test]
+fn test_step_in_and_out() {
+    let mut client = Client::new(SOURCE);
+    client.launch(true);
+    client.request("configurationDone", json!({}));
+    let entry = client.stack_trace();
+    client.request("stepIn", json!({ "threadId": 1 }));
+    let block = client.stack_trace();
+    client.request("stepIn", json!({ "threadId": 1 }));
+    let inside = client.stack_trace();
+    client.request("stepOut", json!({ "threadId": 1 }));
+    let outside = client.stack_trace();
+    client.request("continue", json!({ "threadId": 1 }));
+    let messages = client.finish();
+
+    assert_eq!(
+        stopped_reasons(&messages),
+        ["entry", "step", "step", "step"]
+    );
+    assert_eq!(frame_line(&messages, entry), 1);
+    assert_eq!(frame_line(&messages, block), 2);
+    assert_eq!(frame_line(&messages, inside), 3);
+    assert_eq!(frame_line(&messages, outside), 6);
+}
+
+/// A function called from a block nested deeper than the function's own body.
+const CALL_SOURCE: &str = "var f = fun () {
+  var x = 1;
+  print x;
+};
+{
+  {
+    f();
+    print 2;
+  }
+}
+print 3;
+";
+
+#[test]
+fn test_step_over_call_from_nested_block() {
+    let mut client = Client::new(CALL_SOURCE);
+    client.launch(false);
+    client.set_breakpoints(&[7]);
+    client.request("configurationDone", json!({}));
+    client.request("next", json!({ "threadId": 1 }));
+    let after_call = client.stack_trace();
+    client.request("continue", json!({ "threadId": 1 }));
+    let messages = client.finish();
+
+    assert_eq!(stopped_reasons(&messages), ["breakpoint", "step"]);
+    assert_eq!(frame_line(&messages, after_call), 8);
+}
+
+#[test]
+fn test_step_out_of_function() {
+    let mut client = Client::new(CALL_SOURCE);
+    client.launch(false);
+    client.set_breakpoints(&[2]);
+    client.request("configurationDone", json!({}));
+    client.request("stepOut", json!({ "threadId": 1 }));
+    let caller = client.stack_trace();
+    client.request("continue", json!({ "threadId": 1 }));
+    let messages = client.finish();
+
+    assert_eq!(stopped_reasons(&messages), ["breakpoint", "step"]);
+    assert_eq!(frame_line(&messages, caller), 8);
+}
+
+#[test]
+fn test_string_variables_are_quoted() {
+    let mut client = Client::new("var s = \"a\\\"b\";\nprint s;\n");
+    client.launch(false);
+    client.set_breakpoints(&[2]);
+    client.request("configurationDone", json!({}));
+    let globals = client.variables(1);
+    client.request("continue", json!({ "threadId": 1 }));
+    let messages = client.finish();
+
+    assert_eq!(
+        variables(&messages, globals),
+        [("s".to_owned(), "\"a\\\"b\"".to_owned())]
+    );
+}
+
+#[test]
+fn test_runtime_error_exit_code() {
+    let mut client = Client::new("print 1;\nprint -\"a\";\n");
+    client.launch(false);
+    client.request("configurationDone", json!({}));
+    let messages = client.finish();
+
+    let output: Vec<&Value> = events(&messages, "output").collect();
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[1]["category"], "stderr");
+    let exited: Vec<&Value> = events(&messages, "exited").collect();
+    assert_eq!(exited, [&json!({ "exitCode": 70 })]);
+}
+
+#[test]
+fn test_exit_code() {
+    let mut client = Client::new("exit(3);\nprint 1;\n");
+    client.launch(false);
+    client.request("configurationDone", json!({}));
+    let messages = client.finish();
+
+    let exited: Vec<&Value> = events(&messages, "exited").collect();
+    assert_eq!(exited, [&json!({ "exitCode": 3 })]);
+    assert_eq!(events(&messages, "output").count(), 0);
+}
+
+#[test]
+fn test_read_line_gets_end_of_input() {
+    let mut client = Client::new("print \"before\";\nprint readLine();\n");
+    client.launch(false);
+    client.request("configurationDone", json!({}));
+    let messages = client.finish();
+
+    let output: Vec<&Value> = events(&messages, "output")
+        .map(|body| &body["output"])
+        .collect();
+    assert_eq!(output, ["before\n", "nil\n"]);
+    let exited: Vec<&Value> = events(&messages, "exited").collect();
+    assert_eq!(exited, [&json!({ "exitCode": 0 })]);
+}
+
+#[test]
+fn test_launch_syntax_error() {
+    let mut client = Client::new("var = 1;\n");
+    let launch = client.launch(false);
+    let messages = client.finish();
+
+    let response = response(&messages, launch);
+    assert_eq!(response["success"], false);
+    assert!(
+        response["message"]
+            .as_str()
+            .unwrap()
+            .contains("Expect variable name.")
+    );
+}
+
+#[test]
+fn test_disconnect_while_paused() {
+    let mut client = Client::new(SOURCE);
+    client.launch(true);
+    client.request("configurationDone", json!({}));
+    let messages = client.finish();
+
+    assert_eq!(stopped_reasons(&messages), ["entry"]);
+    assert_eq!(events(&messages, "exited").count(), 0);
+    assert_eq!(events(&messages, "output").count(), 0);
+    assert_eq!(messages.last().unwrap()["command"], "disconnect");
+}
+
+#[test]
+fn test_requests_need_pause() {
+    let mut client = Client::new(SOURCE);
+    let threads = client.request("threads", json!({}));
+    let stack_trace = client.stack_trace();
+    let messages = client.finish();
+
+    assert_eq!(
+        body(&messages, threads)["threads"],
+        json!([{ "id": 1, "name": "main" }])
+    );
+    assert_eq!(response(&messages, stack_trace)["success"], false);
+    assert_eq!(events(&messages, "initialized").count(), 1);
+}
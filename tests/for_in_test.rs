@@ -0,0 +1,172 @@
+use rlox::{
+    ast::{pretty_printer::AstPrinter, reader::AstReader},
+    runner::format_source,
+};
+
+mod common;
+use common::{parse, run, syntax_error};
+
+#[test]
+fn test_ranges() {
+    let source = "for (var i in 0..3) print i;
+for (var i in 2..2) print \"never\";
+var r = 1.5..3;
+print r;
+print type(r);
+print r == 1.5..3;
+for (var x in r) print x;";
+    assert_eq!(
+        run(source),
+        Ok("0\n1\n2\n1.5..3.0\nrange\ntrue\n1.5\n2.5\n".to_owned())
+    );
+    assert_eq!(
+        run("var n = 2; for (var i in n - 1..n + 1) print i;"),
+        Ok("1\n2\n".to_owned())
+    );
+}
+
+#[test]
+fn test_float_ranges_end_without_precision() {
+    // Past 2^53 adding one to a float no longer changes it.
+    assert_eq!(
+        run("for (var x in 9007199254740990.0..9007199254740999.0) print x;"),
+        Ok("9007199254740990.0\n9007199254740991.0\n9007199254740992.0\n".to_owned())
+    );
+    assert_eq!(
+        run("for (var x in 10000000000000000.0..10000000000000003.0) print x;"),
+        Ok("10000000000000000.0\n".to_owned())
+    );
+    assert_eq!(
+        run("for (var x in 10.0 ** 400 * 0..1) print x;"),
+        Ok("".to_owned())
+    );
+}
+
+#[test]
+fn test_strings_lists_and_maps() {
+    let source = "for (var c in \"héy\") print c;
+for (var x in [1, \"a\", nil]) print x;
+var m = {\"b\": 1, \"a\": 2};
+for (var k in m) print k + \"=\" + str(m[k]);";
+    assert_eq!(run(source), Ok("h\né\ny\n1\na\nnil\nb=1\na=2\n".to_owned()));
+}
+
+#[test]
+fn test_changes_while_looping() {
+    // Elements pushed to a list while looping are reached.
+    let source = "var xs = [1];
+for (var x in xs) if (x < 3) push(xs, x + 1);
+print xs;
+var m = {\"a\": 1};
+for (var k in m) m[k + k] = 1;
+print m;";
+    assert_eq!(
+        run(source),
+        Ok("[1, 2, 3]\n{\"a\": 1, \"aa\": 1}\n".to_owned())
+    );
+}
+
+#[test]
+fn test_iterator_protocol() {
+    let source = "var countdown = fun (from) {
+  return {\"iter\": fun () {
+    var n = from;
+    return {\"next\": fun () {
+      if (n == 0) return nil;
+      n = n - 1;
+      return n + 1;
+    }};
+  }};
+};
+for (var i in countdown(3)) print i;
+var twice = countdown(2);
+for (var i in twice) print i;
+for (var i in twice) print i;";
+    assert_eq!(run(source), Ok("3\n2\n1\n2\n1\n2\n1\n".to_owned()));
+    // Other maps are iterated by keys, even with a `next` function.
+    assert_eq!(
+        run("for (var k in {\"next\": fun () { return 1; }, \"iter\": 1}) print k;"),
+        Ok("next\niter\n".to_owned())
+    );
+}
+
+#[test]
+fn test_break_continue_and_return() {
+    let source = "for (var i in 0..10) {
+  if (i == 1) continue;
+  if (i == 3) break;
+  print i;
+}
+var first = fun (xs) { for (var x in xs) return x; };
+print first(\"ab\");
+print first([]);";
+    assert_eq!(run(source), Ok("0\n2\na\nnil\n".to_owned()));
+}
+
+#[test]
+fn test_loop_variable_scope() {
+    let source = "var i = \"outer\";
+var fs = [];
+for (var i in 0..3) push(fs, () => i);
+print i;
+print fs[0]() + fs[2]();";
+    assert_eq!(run(source), Ok("outer\n2\n".to_owned()));
+}
+
+#[test]
+fn test_runtime_errors() {
+    assert_eq!(
+        run("for (var x in 1) {}"),
+        Err(
+            "Runtime error: Can only iterate over strings, lists, maps, ranges and iterators, got int."
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        run("print 1..\"a\";"),
+        Err("Runtime error: Operands must be two numbers.".to_owned())
+    );
+    assert_eq!(
+        run("for (var x in {\"iter\": () => {\"next\": (a) => a}}) {}"),
+        Err("Runtime error: Expected 1 arguments but got 0.".to_owned())
+    );
+    assert_eq!(
+        run("for (var x in {\"iter\": () => {\"next\": 1}}) {}"),
+        Err("Runtime error: iter() must return a map with a 'next' function, got map.".to_owned())
+    );
+}
+
+#[test]
+fn test_syntax_errors() {
+    assert_eq!(
+        syntax_error("for (x in xs) {}"),
+        "Expect 'var' after 'for ('."
+    );
+    assert_eq!(
+        syntax_error("for (var x of xs) {}"),
+        "Expect 'in' after variable name."
+    );
+    assert_eq!(syntax_error("print 0..1..2;"), "Expect ';' after value");
+}
+
+#[test]
+fn test_printer_and_reader() {
+    let source = "for (var x in 0..n + 1) { print x; } for (var c in \"ab\") break;";
+    let program = parse(source).parse().unwrap();
+    let printed = AstPrinter().fmt_stmt(&program);
+    assert_eq!(
+        printed,
+        "[(for x (.. 0 (+ n 1)) [(print x)]);(for c \"ab\" (break))]"
+    );
+    let read = AstReader::read_program(&printed).unwrap();
+    assert_eq!(AstPrinter().fmt_stmt(&read), printed);
+}
+
+#[test]
+fn test_formatter() {
+    assert_eq!(
+        format_source("for(var i in (0..(n+1))){print i;}\nfor (var c in s) print (0..1)..2;")
+            .unwrap(),
+        "for (var i in 0..n + 1) {\n  print i;\n}\nfor (var c in s)\n  print (0..1)..2;\n"
+    );
+}
@@ -0,0 +1,115 @@
+use rlox::runner::format_source;
+
+fn format(source: &str) -> String {
+    format_source(source).unwrap()
+}
+
+#[test]
+fn test_spacing_and_indentation() {
+    let source = "var a=1;{var b=a+2;{print b*3;}}print   a;";
+    let expected = "\
+var a = 1;
+{
+  var b = a + 2;
+  {
+    print b * 3;
+  }
+}
+print a;
+";
+    assert_eq!(format(source), expected);
+}
+
+#[test]
+fn test_minimal_parentheses() {
+    let cases = [
+        ("print (1 + 2) * 3;", "print (1 + 2) * 3;\n"),
+        ("print 1 + (2 * 3);", "print 1 + 2 * 3;\n"),
+        ("print ((1));", "print 1;\n"),
+        ("print 1 - (2 - 3);", "print 1 - (2 - 3);\n"),
+        ("print (1 - 2) - 3;", "print 1 - 2 - 3;\n"),
+        ("print -(1 + 2);", "print -(1 + 2);\n"),
+        ("print (-1) + 2;", "print -1 + 2;\n"),
+        ("a = (b = 1);", "a = b = 1;\n"),
+        ("print (a ? b : c) ? d : e;", "print (a ? b : c) ? d : e;\n"),
+        ("print a ? (b) : (c ? d : e);", "print a ? b : c ? d : e;\n"),
+        ("print (f)(1, (2));", "print f(1, 2);\n"),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(format(source), expected, "source: {source}");
+    }
+}
+
+#[test]
+fn test_literals() {
+    assert_eq!(
+        format("print \"a \\\"quoted\\\"\\n\" + \"b\";print 1.50;print nil;"),
+        "print \"a \\\"quoted\\\"\\n\" + \"b\";\nprint 1.5;\nprint nil;\n"
+    );
+}
+
+#[test]
+fn test_comments_preserved() {
+    let source = "\
+// header comment
+var a = 1; // trailing
+
+
+// before block
+{ // open
+  print a;
+  // last in block
+} // close
+// end of file
+";
+    let expected = "\
+// header comment
+var a = 1; // trailing
+
+// before block
+{ // open
+  print a;
+  // last in block
+} // close
+// end of file
+";
+    assert_eq!(format(source), expected);
+}
+
+#[test]
+fn test_test_blocks_and_empty_blocks() {
+    assert_eq!(
+        format("test \"it works\" {assert(true);} {}"),
+        "test \"it works\" {\n  assert(true);\n}\n{}\n"
+    );
+}
+
+#[test]
+fn test_while_bodies() {
+    assert_eq!(
+        format("while(i<3){i=i+1;}"),
+        "while (i < 3) {\n  i = i + 1;\n}\n"
+    );
+    assert_eq!(
+        format("while (i < 3) i = i + 1; // step"),
+        "while (i < 3)\n  i = i + 1; // step\n"
+    );
+}
+
+#[test]
+fn test_idempotent() {
+    let sources = [
+        "var a=1;{var b=a+2;{print b*3;}}print   a;",
+        "// c\nvar x = ((1 + 2)) * -(3);\n\n\n\nprint x ? (1) : 2; // t\n{\n\n// inner\n}\n",
+        "print a = b = (c ? d : e) == f;",
+    ];
+    for source in sources {
+        let once = format(source);
+        assert_eq!(format(&once), once, "source: {source}");
+    }
+}
+
+#[test]
+fn test_invalid_source() {
+    assert!(format_source("var = ;").is_err());
+}
@@ -0,0 +1,32 @@
+use rlox::{
+    ast::{pretty_printer::AstPrinter, reader::AstReader},
+    parser::Parser,
+    runner::format_source,
+    scanner::Scanner,
+};
+
+#[test]
+fn test_printer_and_reader() {
+    let source = "if (a) print 1; else if (b) { print 2; } if (c) print 3;";
+    let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let printed = AstPrinter().fmt_stmt(&program);
+    assert_eq!(
+        printed,
+        "[(if a (print 1) (if b [(print 2)]));(if c (print 3))]"
+    );
+    let read = AstReader::read_program(&printed).unwrap();
+    assert_eq!(AstPrinter().fmt_stmt(&read), printed);
+}
+
+#[test]
+fn test_formatter() {
+    assert_eq!(
+        format_source("if(a){print 1;}else if(b)print 2;else{print 3;}").unwrap(),
+        "if (a) {\n  print 1;\n} else if (b)\n  print 2;\nelse {\n  print 3;\n}\n"
+    );
+    assert_eq!(
+        format_source("if (a) {}\nelse print 2; // two").unwrap(),
+        "if (a) {} else\n  print 2; // two\n"
+    );
+}
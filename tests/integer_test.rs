@@ -0,0 +1,191 @@
+use rlox::{
+    ast::{pretty_printer::AstPrinter, reader::AstReader},
+    parser::Parser,
+    runner::format_source,
+    scanner::Scanner,
+    token::LiteralType,
+    value::LoxValue,
+};
+
+mod common;
+use common::{assert_evals, eval, syntax_error};
+
+#[test]
+fn test_literals() {
+    let tokens = Scanner::new("1 1.0 9007199254740993".to_owned())
+        .scan_tokens()
+        .unwrap();
+    let literals: Vec<&LiteralType> = tokens[..3].iter().map(|t| &t.literal).collect();
+    assert_eq!(
+        literals,
+        vec![
+            &LiteralType::Int(1),
+            &LiteralType::Number(1.0),
+            &LiteralType::Int(9007199254740993),
+        ]
+    );
+}
+
+#[test]
+fn test_smallest_int_literal() {
+    assert_evals(&[
+        ("-9223372036854775808", "-9223372036854775808"),
+        ("type(-9223372036854775808)", "int"),
+        ("-9223372036854775808 == -9223372036854775807 - 1", "true"),
+        ("-1 - -9223372036854775808", "9223372036854775807"),
+    ]);
+    for source in [
+        "print 9223372036854775808;",
+        "print 1 - 9223372036854775808;",
+        "print 99999999999999999999;",
+        // `**` binds tighter than the minus.
+        "print -9223372036854775808 ** 1;",
+    ] {
+        assert_eq!(
+            syntax_error(source),
+            "Integer literal out of range, add an 'n' suffix for a big integer.",
+            "{source}"
+        );
+    }
+    assert_eq!(
+        format_source("print -9223372036854775808; print (-9223372036854775808) ** 1;").unwrap(),
+        "print -9223372036854775808;\nprint (-9223372036854775808) ** 1;\n"
+    );
+    let tokens = Scanner::new("1 - -9223372036854775808".to_owned())
+        .scan_tokens()
+        .unwrap();
+    let printed = AstPrinter().fmt(&Parser::new(tokens).parse_expr().unwrap());
+    assert_eq!(printed, "(- 1 -9223372036854775808)");
+    assert_eq!(
+        AstPrinter().fmt(&AstReader::read_expr(&printed).unwrap()),
+        printed
+    );
+}
+
+#[test]
+fn test_display() {
+    assert_evals(&[
+        ("1", "1"),
+        ("1.0", "1.0"),
+        ("-0.0", "-0.0"),
+        ("2.5", "2.5"),
+        ("9007199254740993", "9007199254740993"),
+        (
+            "1000000.0 * 1000000.0 * 1000000000.0",
+            "1000000000000000000000.0",
+        ),
+        ("0..3", "0..3"),
+        ("0..2.5", "0.0..2.5"),
+        ("[1, 1.0]", "[1, 1.0]"),
+    ]);
+}
+
+#[test]
+fn test_promotion() {
+    assert_evals(&[
+        ("1 + 2", "3"),
+        ("1 + 2.0", "3.0"),
+        ("2.5 * 2", "5.0"),
+        ("6 / 3", "2.0"),
+        ("7 / 2", "3.5"),
+        ("7 ~/ 2", "3"),
+        ("-7 ~/ 2", "-4"),
+        ("7.0 ~/ 2", "3.0"),
+        ("-7 % 3", "2"),
+        ("7 % -3", "-2"),
+        ("-7.5 % 2", "0.5"),
+        ("2 ** 10", "1024"),
+        ("2 ** -1", "0.5"),
+        ("2.0 ** 2", "4.0"),
+        ("-5", "-5"),
+        ("1 == 1.0", "true"),
+        ("1 == 1.5", "false"),
+        ("1 < 1.5", "true"),
+        ("9007199254740993 == 9007199254740992", "false"),
+        ("type(1) + type(1.0)", "intfloat"),
+    ]);
+}
+
+#[test]
+fn test_overflow() {
+    let cases = [
+        ("9223372036854775807 + 1", "Integer overflow in '+'."),
+        ("-9223372036854775807 - 2", "Integer overflow in '-'."),
+        ("4294967296 * 4294967296", "Integer overflow in '*'."),
+        ("2 ** 63", "Integer overflow in '**'."),
+        ("-(-9223372036854775807 - 1)", "Integer overflow in '-'."),
+        (
+            "(-9223372036854775807 - 1) ~/ -1",
+            "Integer overflow in '~/'.",
+        ),
+        (
+            "abs(-9223372036854775807 - 1)",
+            "Integer overflow in abs().",
+        ),
+        ("1 / 0", "can not divided by zero"),
+        ("1 % 0.0", "Modulo by zero."),
+    ];
+    for (source, message) in cases {
+        assert_eq!(
+            eval(source),
+            Err(format!("Runtime error: {message}")),
+            "{source}"
+        );
+    }
+    // Floats round instead.
+    assert_evals(&[("9223372036854775807 + 1.0", "9223372036854775808.0")]);
+}
+
+#[test]
+fn test_natives() {
+    assert_evals(&[
+        ("int(2.9)", "2"),
+        ("int(-2.9)", "-2"),
+        ("int(7)", "7"),
+        ("float(7)", "7.0"),
+        ("floor(2.5)", "2"),
+        ("ceil(2)", "2"),
+        ("min(3, 1.5, 2)", "1.5"),
+        ("max(3, 1.5, 2)", "3"),
+        ("num(\"12\")", "12"),
+        ("num(\"12.0\")", "12.0"),
+        ("len(\"abc\")", "3"),
+    ]);
+    assert_eq!(
+        eval("int(10.0 ** 400)"),
+        Err("Runtime error: int() can't convert inf to an int.".to_owned())
+    );
+}
+
+#[test]
+fn test_map_keys_and_indexes() {
+    assert_evals(&[
+        ("{1: \"a\"}[1.0]", "a"),
+        ("{1.0: \"a\", 1: \"b\"}", "{1: \"b\"}"),
+        ("[1, 2][1]", "2"),
+    ]);
+    assert_eq!(
+        eval("[1, 2][1.0]"),
+        Err("Runtime error: List index must be a non negative integer, got 1.0.".to_owned())
+    );
+    assert_eq!(
+        LoxValue::Int(1).to_string() + &LoxValue::Number(1.0).to_string(),
+        "11.0"
+    );
+}
+
+#[test]
+fn test_floats_survive_printing() {
+    assert_eq!(
+        format_source("print 1.0 + 2 * 3.50;").unwrap(),
+        "print 1.0 + 2 * 3.5;\n"
+    );
+    let tokens = Scanner::new("1 + 1.0".to_owned()).scan_tokens().unwrap();
+    let expr = Parser::new(tokens).parse_expr().unwrap();
+    let printed = AstPrinter().fmt(&expr);
+    assert_eq!(printed, "(+ 1 1.0)");
+    assert_eq!(
+        AstPrinter().fmt(&AstReader::read_expr(&printed).unwrap()),
+        printed
+    );
+}
@@ -18,6 +18,25 @@ fn test_cases() -> Vec<(&'static str, LoxValue)> {
         ("123.45 - 54.321", LoxValue::Number(123.45 - 54.321)),
         ("123.45 * 54.321", LoxValue::Number(123.45 * 54.321)),
         ("123.45 / 54.321", LoxValue::Number(123.45 / 54.321)),
+        ("7 % 3", LoxValue::Int(1)),
+        ("-7 % 3", LoxValue::Int(2)),
+        ("7 % -3", LoxValue::Int(-2)),
+        ("5.5 % 2", LoxValue::Number(1.5)),
+        ("7 ~/ 2", LoxValue::Int(3)),
+        ("-7 ~/ 2", LoxValue::Int(-4)),
+        ("2 ** 10", LoxValue::Int(1024)),
+        ("2 ** -1", LoxValue::Number(0.5)),
+        ("-2 ** 2", LoxValue::Int(-4)),
+        ("(-2) ** 2", LoxValue::Int(4)),
+        ("2 ** 3 ** 2", LoxValue::Int(512)),
+        ("2 * 3 ** 2 % 5", LoxValue::Int(3)),
+        ("12 & 10", LoxValue::Int(8)),
+        ("12 | 3", LoxValue::Int(15)),
+        ("12 ^ 10", LoxValue::Int(6)),
+        ("~5", LoxValue::Int(-6)),
+        ("1 << 10", LoxValue::Int(1024)),
+        ("-16 >> 2", LoxValue::Int(-4)),
+        ("1 | 2 ^ 3 & 4 << 1", LoxValue::Int(3)),
         (
             "\"hello\" + \" lox\"",
             LoxValue::String("hello lox".to_owned()),
@@ -33,15 +52,15 @@ fn test_cases() -> Vec<(&'static str, LoxValue)> {
         ("true == 1.0", LoxValue::Bool(false)),
         ("true != 1.0", LoxValue::Bool(true)),
         // ternary
-        ("1 < 2 ? 1 : 2", LoxValue::Number(1.0)),
-        ("1 > 2 ? 1 : 2", LoxValue::Number(2.0)),
+        ("1 < 2 ? 1 : 2", LoxValue::Int(1)),
+        ("1 > 2 ? 1 : 2", LoxValue::Int(2)),
         // Lox is a dynamic type language, so it's ternary expression can return union type value.
         // In the following situation, the return type of ternary operator is `number | string`.
-        ("1 < 2 ? 1 : \"abc\"", LoxValue::Number(1.0)),
+        ("1 < 2 ? 1 : \"abc\"", LoxValue::Int(1)),
         ("1 > 2 ? 1 : \"abc\"", LoxValue::String("abc".to_owned())),
         // grouping
         ("!(1 > 2)", LoxValue::Bool(!(1 > 2))),
-        ("-((1 + 2) * 3)", LoxValue::Number(-((1.0 + 2.0) * 3.0))),
+        ("-((1 + 2) * 3)", LoxValue::Int(-((1 + 2) * 3))),
         (
             "(1 + 2) * 3 / ((6 - 2) * 1 / 2)",
             LoxValue::Number((1.0 + 2.0) * 3.0 / ((6.0 - 2.0) * 1.0 / 2.0)),
@@ -60,6 +79,9 @@ fn test_evaluation() {
         assert!(program.is_some());
         let evaluated = program.unwrap().accept(&mut interpreter);
         assert!(evaluated.is_ok());
-        assert_eq!(expected, evaluated.unwrap());
+        let evaluated = evaluated.unwrap();
+        // `==` promotes ints, so check that the result kept the expected type.
+        assert_eq!(expected.type_name(), evaluated.type_name(), "{input}");
+        assert_eq!(expected, evaluated);
     });
 }
@@ -0,0 +1,77 @@
+use std::{
+    io,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use rlox::{
+    ast::stmt::Stmt,
+    error::{Interruption, RloxError},
+    interpreter::{Interpreter, Limits},
+    parser::Parser,
+    scanner::Scanner,
+};
+
+fn parse(source: &str) -> Stmt {
+    let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+#[test]
+fn test_interrupt_infinite_loop_from_another_thread() {
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output(Box::new(io::sink()));
+        sender.send(interpreter.interrupt_handle()).unwrap();
+        interpreter.execute(&parse("var i = 0; while (true) i = i + 1;"))
+    });
+
+    let handle = receiver.recv().unwrap();
+    thread::sleep(Duration::from_millis(20));
+    handle.interrupt();
+    let result = worker.join().unwrap();
+    assert!(matches!(
+        result,
+        Err(RloxError::Interrupted(Interruption::Cancelled))
+    ));
+}
+
+#[test]
+fn test_interrupt_is_cleared_once_reported() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(io::sink()));
+    interpreter.interrupt_handle().interrupt();
+    let program = parse("print 1;");
+    assert!(matches!(
+        interpreter.execute(&program),
+        Err(RloxError::Interrupted(Interruption::Cancelled))
+    ));
+    assert!(interpreter.execute(&program).is_ok());
+}
+
+#[test]
+fn test_timeout_stops_infinite_loop() {
+    let timeout = Duration::from_millis(50);
+    let mut interpreter = Interpreter::new();
+    interpreter.set_limits(Limits {
+        timeout: Some(timeout),
+        ..Limits::default()
+    });
+    let start = Instant::now();
+    let result = interpreter.execute(&parse("while (true) {}"));
+    assert!(start.elapsed() >= timeout);
+    assert!(matches!(
+        result,
+        Err(RloxError::Interrupted(Interruption::TimedOut(t))) if t == timeout
+    ));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Runtime error: Timed out after 50ms."
+    );
+
+    // Each run gets the full timeout.
+    interpreter.set_output(Box::new(io::sink()));
+    assert!(interpreter.execute(&parse("print 1;")).is_ok());
+}
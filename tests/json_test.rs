@@ -0,0 +1,70 @@
+#![cfg(feature = "json")]
+
+use rlox::{
+    ast::stmt::Stmt,
+    parser::Parser,
+    runner::{dump_ast_json, dump_tokens_json},
+    scanner::Scanner,
+    token::{Span, Token, TokenType},
+};
+
+const SOURCE: &str = "var a = 1;\n{ print a + \"x\"; }\ntest \"t\" { assert(a); }";
+
+#[test]
+fn test_tokens_round_trip() {
+    let json = dump_tokens_json(SOURCE).unwrap();
+    let tokens: Vec<Token> = serde_json::from_str(&json).unwrap();
+    let mut scanner = Scanner::new(SOURCE.to_owned());
+    assert_eq!(tokens, scanner.scan_tokens().unwrap());
+}
+
+#[test]
+fn test_token_json_shape() {
+    let json = dump_tokens_json("var a").unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value[1],
+        serde_json::json!({
+            "token_type": "Identifier",
+            "lexeme": "a",
+            "literal": "Nil",
+            "line": 1,
+            "span": { "start": 4, "end": 5 },
+        })
+    );
+}
+
+#[test]
+fn test_token_spans() {
+    let mut scanner = Scanner::new("print \"hi\";\nx".to_owned());
+    let spans: Vec<(TokenType, Span)> = scanner
+        .scan_tokens()
+        .unwrap()
+        .into_iter()
+        .map(|t| (t.token_type, t.span))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            (TokenType::Print, Span { start: 0, end: 5 }),
+            (TokenType::String, Span { start: 6, end: 10 }),
+            (TokenType::Semicolon, Span { start: 10, end: 11 }),
+            (TokenType::Identifier, Span { start: 12, end: 13 }),
+            (TokenType::Eof, Span { start: 13, end: 13 }),
+        ]
+    );
+}
+
+#[test]
+fn test_ast_round_trip() {
+    let json = dump_ast_json(SOURCE).unwrap();
+    let program: Stmt = serde_json::from_str(&json).unwrap();
+    let mut scanner = Scanner::new(SOURCE.to_owned());
+    let mut parser = Parser::new(scanner.scan_tokens().unwrap());
+    assert_eq!(program, parser.parse().unwrap());
+}
+
+#[test]
+fn test_ast_invalid_source() {
+    assert!(dump_ast_json("var = ;").is_err());
+}
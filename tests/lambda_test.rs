@@ -0,0 +1,180 @@
+use std::io;
+
+use rlox::{
+    ast::{pretty_printer::AstPrinter, reader::AstReader},
+    interpreter::Interpreter,
+    runner::{format_source, run_repl},
+};
+
+mod common;
+use common::{Buffer, parse, run, syntax_error};
+
+#[test]
+fn test_call_and_return() {
+    let source = "var add = fun (a, b) { return a + b; };
+print add(1, 2);
+print fun () {}();
+print fun () { return; }();
+print add;
+print type(add);";
+    assert_eq!(run(source), Ok("3\nnil\nnil\n<fn>\nfunction\n".to_owned()));
+}
+
+#[test]
+fn test_arrow_functions() {
+    let source = "var double = (a) => a * 2;
+var pick = (a, b) => a > b ? a : b;
+var answer = () => 42;
+print double(4);
+print pick(1, 3);
+print answer();";
+    assert_eq!(run(source), Ok("8\n3\n42\n".to_owned()));
+    // Parentheses which don't hold parameters stay a grouping.
+    assert_eq!(run("var a = 1; print (a) + (1 + a);"), Ok("3\n".to_owned()));
+}
+
+#[test]
+fn test_functions_as_values() {
+    let source = "var apply = fun (f, x) { return f(x); };
+print apply((n) => n + 1, 1);
+var fs = [(x) => x, (x) => -x];
+print fs[1](3);
+var twice = (f) => (x) => f(f(x));
+print twice((x) => x * 3)(2);";
+    assert_eq!(run(source), Ok("2\n-3\n18\n".to_owned()));
+}
+
+#[test]
+fn test_closures() {
+    let source = "var counter = fun () {
+  var count = 0;
+  return fun () {
+    count = count + 1;
+    return count;
+  };
+};
+var a = counter();
+var b = counter();
+a();
+print a();
+print b();";
+    assert_eq!(run(source), Ok("2\n1\n".to_owned()));
+}
+
+#[test]
+fn test_closure_sees_later_changes() {
+    let source = "var x = 1;
+var get = () => x;
+x = 2;
+print get();
+{
+  var y = \"block\";
+  var show = () => y;
+  y = \"changed\";
+  print show();
+}";
+    assert_eq!(run(source), Ok("2\nchanged\n".to_owned()));
+}
+
+#[test]
+fn test_recursion() {
+    let source = "var fib = fun (n) {
+  if (n < 2) return n;
+  return fib(n - 1) + fib(n - 2);
+};
+print fib(10);";
+    assert_eq!(run(source), Ok("55\n".to_owned()));
+}
+
+#[test]
+fn test_return_from_loop() {
+    let source = "var find = fun (xs, x) {
+  var i = 0;
+  while (i < len(xs)) {
+    if (xs[i] == x) return i;
+    i = i + 1;
+  }
+  return -1;
+};
+print find([3, 4, 5], 5);
+print find([3], 1);";
+    assert_eq!(run(source), Ok("2\n-1\n".to_owned()));
+}
+
+#[test]
+fn test_runtime_errors() {
+    assert_eq!(
+        run("var f = (a) => a; f(1, 2);"),
+        Err("Runtime error: Expected 1 arguments but got 2.".to_owned())
+    );
+    assert_eq!(
+        run("var f = () => undefined; f();"),
+        Err("Runtime error: Undefined variable: undefined.".to_owned())
+    );
+}
+
+#[test]
+fn test_caller_scope_restored_after_error() {
+    let output = Buffer::default();
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(output.clone()));
+    interpreter.set_error_output(Box::new(io::sink()));
+    let lines = "var a = \"global\";
+var f = fun (a) { { var b = a; nil(); } };
+f(1);
+print a;
+";
+    run_repl(lines.as_bytes(), &mut interpreter).unwrap();
+    assert_eq!(output.take(), "global\n");
+    assert_eq!(interpreter.environment.depth(), 0);
+}
+
+#[test]
+fn test_syntax_errors() {
+    assert_eq!(
+        syntax_error("return 1;"),
+        "Can't return from top-level code."
+    );
+    assert_eq!(
+        syntax_error("while (true) { var f = fun () { break; }; }"),
+        "Can't use 'break' outside of a loop."
+    );
+    assert_eq!(
+        syntax_error("var f = fun (a, 1) {};"),
+        "Expect parameter name."
+    );
+    assert_eq!(
+        syntax_error("var f = fun (a) return a;"),
+        "Expect '{' before function body."
+    );
+}
+
+#[test]
+fn test_printer_and_reader() {
+    let source = "var f = fun (a, b) { while (a) return; return a + b; }; var g = () => (x) => x;";
+    let program = parse(source).parse().unwrap();
+    let printed = AstPrinter().fmt_stmt(&program);
+    assert_eq!(
+        printed,
+        "[(var f = (fun (a b) [(while a (return));(return (+ a b))]));(var g = (=> () (=> (x) x)))]"
+    );
+    let read = AstReader::read_program(&printed).unwrap();
+    assert_eq!(AstPrinter().fmt_stmt(&read), printed);
+}
+
+#[test]
+fn test_formatter() {
+    assert_eq!(
+        format_source("var f=fun(a,b){return a+b;};\nprint f(1,2);").unwrap(),
+        "var f = fun (a, b) {\n  return a + b;\n};\nprint f(1, 2);\n"
+    );
+    assert_eq!(
+        format_source("{\nvar g = fun () {\n// note\nprint 1;\n}; // g\n\nprint ((x) => x + 1)(2)+(fun(){})();\n}")
+            .unwrap(),
+        "{\n  var g = fun () {\n    // note\n    print 1;\n  }; // g\n\n  print ((x) => x + 1)(2) + fun () {}();\n}\n"
+    );
+    assert_eq!(
+        format_source("var h = (a) => (1 + a);").unwrap(),
+        "var h = (a) => 1 + a;\n"
+    );
+}
@@ -0,0 +1,148 @@
+use std::{io, thread};
+
+use rlox::{
+    ast::stmt::Stmt,
+    error::RloxError,
+    interpreter::{DEFAULT_MAX_DEPTH, Interpreter, Limits},
+    parser::Parser,
+    scanner::Scanner,
+};
+
+fn parse(source: &str) -> (Option<Stmt>, Parser) {
+    let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+    let mut parser = Parser::new(tokens);
+    (parser.parse(), parser)
+}
+
+/// Run `f` on a stack the size of the main thread's, test threads having a smaller one.
+fn with_main_stack<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+fn run(source: &str, limits: Limits) -> Result<(), RloxError> {
+    let (program, parser) = parse(source);
+    assert!(!parser.had_error, "Failed to parse {source}");
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(io::sink()));
+    interpreter.set_limits(limits);
+    interpreter.execute(&program.unwrap())
+}
+
+#[test]
+fn test_step_limit_stops_infinite_loop() {
+    let limits = Limits {
+        max_steps: Some(10_000),
+        ..Limits::default()
+    };
+    let result = run("while (true) {}", limits);
+    assert!(matches!(result, Err(RloxError::StepLimitExceeded(10_000))));
+}
+
+#[test]
+fn test_step_limit_allows_short_programs() {
+    let limits = Limits {
+        max_steps: Some(10_000),
+        ..Limits::default()
+    };
+    let source = "var i = 0; while (i < 10) i = i + 1;";
+    assert!(run(source, limits).is_ok());
+}
+
+#[test]
+fn test_depth_limit() {
+    let source = format!("print {}1{};", "(".repeat(100), ")".repeat(100));
+    let limits = Limits {
+        max_depth: Some(50),
+        ..Limits::default()
+    };
+    let (limited, unlimited) = with_main_stack(move || {
+        (
+            run(&source, limits),
+            run(&source, Limits::default()).is_ok(),
+        )
+    });
+    assert!(matches!(limited, Err(RloxError::DepthLimitExceeded(50))));
+    assert!(unlimited);
+}
+
+#[test]
+fn test_unbounded_recursion_hits_depth_limit() {
+    let source = "var f = fun (n) { return f(n + 1); }; f(0);";
+    let result = with_main_stack(move || run(source, Limits::default()));
+    assert!(matches!(
+        result,
+        Err(RloxError::DepthLimitExceeded(DEFAULT_MAX_DEPTH))
+    ));
+}
+
+#[test]
+fn test_default_depth_allows_recursion() {
+    let source = "var f = fun (n) { if (n == 0) return 0; return f(n - 1) + 1; }; print f(450);";
+    let result = with_main_stack(move || run(source, Limits::default()).map_err(|e| e.to_string()));
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn test_deepest_nesting_fits_main_thread_stack() {
+    // Nesting just below the parser limit.
+    let source = format!(
+        "{} print {}-1{}; {}",
+        "{".repeat(60),
+        "(".repeat(60),
+        ")".repeat(60),
+        "}".repeat(60)
+    );
+    with_main_stack(move || run(&source, Limits::default()).map_err(|e| e.to_string())).unwrap();
+}
+
+#[test]
+fn test_parser_rejects_too_much_nesting() {
+    let errors = with_main_stack(|| {
+        let source = format!("print {}1{};", "(".repeat(10_000), ")".repeat(10_000));
+        let (_, parser) = parse(&source);
+        parser
+            .errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    });
+    assert!(errors[0].contains("Too much nesting."), "{}", errors[0]);
+
+    let errors = with_main_stack(|| {
+        let source = format!("print {}1;", "-".repeat(10_000));
+        parse(&source).1.errors.len()
+    });
+    assert!(errors > 0);
+}
+
+#[test]
+fn test_string_memory_limit() {
+    let limits = Limits {
+        max_string_bytes: Some(1024),
+        ..Limits::default()
+    };
+    let source = "var s = \"ab\"; while (true) s = s + s;";
+    let result = run(source, limits);
+    assert!(matches!(result, Err(RloxError::MemoryLimitExceeded(1024))));
+}
+
+#[test]
+fn test_limit_errors_are_distinct() {
+    assert_eq!(
+        RloxError::StepLimitExceeded(5).to_string(),
+        "Runtime error: Step limit of 5 exceeded."
+    );
+    assert_eq!(
+        RloxError::DepthLimitExceeded(5).to_string(),
+        "Runtime error: Depth limit of 5 exceeded."
+    );
+    assert_eq!(
+        RloxError::MemoryLimitExceeded(5).to_string(),
+        "Runtime error: String memory limit of 5 bytes exceeded."
+    );
+}
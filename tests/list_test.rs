@@ -0,0 +1,145 @@
+use rlox::{
+    ast::pretty_printer::AstPrinter, parser::Parser, runner::format_source, scanner::Scanner,
+    value::LoxValue,
+};
+
+mod common;
+use common::run;
+
+#[test]
+fn test_literals_and_display() {
+    assert_eq!(
+        run("print []; print [1, \"a\", nil, [true]]; print type([]);"),
+        Ok("[]\n[1, \"a\", nil, [true]]\nlist\n".to_owned())
+    );
+}
+
+#[test]
+fn test_index_read_and_write() {
+    let source = "var xs = [1, 2, 3];
+print xs[0] + xs[2];
+print xs[1] = 20;
+xs[2] = [4];
+xs[2][0] = 5;
+print xs;
+print [7, 8][1];";
+    assert_eq!(run(source), Ok("4\n20\n[1, 20, [5]]\n8\n".to_owned()));
+}
+
+#[test]
+fn test_index_errors() {
+    let cases = [
+        ("[1, 2][2];", "Index 2 out of bounds for list of length 2."),
+        (
+            "[1][-1];",
+            "List index must be a non negative integer, got -1.",
+        ),
+        (
+            "[1][0.5];",
+            "List index must be a non negative integer, got 0.5.",
+        ),
+        (
+            "[1][\"0\"] = 1;",
+            "List index must be a non negative integer, got \"0\".",
+        ),
+        ("var s = \"ab\"; s[0];", "Can only index lists and maps."),
+        ("nil[0] = 1;", "Can only index lists and maps."),
+    ];
+    for (source, message) in cases {
+        assert_eq!(
+            run(source),
+            Err(format!("Runtime error: {message}")),
+            "source: {source}"
+        );
+    }
+}
+
+#[test]
+fn test_reference_semantics() {
+    let source = "var a = [1];
+var b = a;
+push(b, 2);
+b[0] = 0;
+print a;
+print a == b;
+print a == [0, 2];
+push(a, a);
+print a;";
+    assert_eq!(
+        run(source),
+        Ok("[0, 2]\ntrue\nfalse\n[0, 2, [...]]\n".to_owned())
+    );
+}
+
+#[test]
+fn test_natives() {
+    let source = "var xs = [];
+push(xs, 1);
+push(xs, 3);
+insert(xs, 1, 2);
+insert(xs, 3, 4);
+print xs;
+print len(xs);
+print pop(xs);
+print remove(xs, 0);
+print xs;";
+    assert_eq!(
+        run(source),
+        Ok("[1, 2, 3, 4]\n4\n4\n1\n[2, 3]\n".to_owned())
+    );
+
+    let cases = [
+        ("pop([]);", "pop() on an empty list."),
+        (
+            "insert([], 1, 0);",
+            "insert() index 1 out of bounds for list of length 0.",
+        ),
+        (
+            "remove([1], 1);",
+            "remove() index 1 out of bounds for list of length 1.",
+        ),
+        ("push(1, 2);", "push() argument 1 must be a list, got int."),
+    ];
+    for (source, message) in cases {
+        assert_eq!(
+            run(source),
+            Err(format!("Runtime error: {message}")),
+            "source: {source}"
+        );
+    }
+}
+
+#[test]
+fn test_list_value() {
+    let list = LoxValue::list(vec![LoxValue::Int(1), LoxValue::String("a".to_owned())]);
+    assert_eq!(list.to_string(), "[1, \"a\"]");
+    assert_eq!(list, list.clone());
+    assert_ne!(list, LoxValue::list(vec![]));
+}
+
+#[test]
+fn test_printer_and_formatter() {
+    let tokens = Scanner::new("xs[0] = [1, ys[2]]".to_owned())
+        .scan_tokens()
+        .unwrap();
+    let expr = Parser::new(tokens).parse_expr().unwrap();
+    assert_eq!(
+        AstPrinter().fmt(&expr),
+        "(index= xs 0 (list 1 (index ys 2)))"
+    );
+
+    assert_eq!(
+        format_source("print [ 1,(2) ] [0];xs [ i ]=( a = 1 );").unwrap(),
+        "print [1, 2][0];\nxs[i] = a = 1;\n"
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    for source in ["print [1, 2;", "print xs[1;", "[1] + 1 = 2;"] {
+        let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse();
+        assert!(parser.had_error, "source: {source}");
+    }
+}
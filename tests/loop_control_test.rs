@@ -0,0 +1,119 @@
+use rlox::{
+    ast::{pretty_printer::AstPrinter, reader::AstReader},
+    runner::format_source,
+};
+
+mod common;
+use common::{parse, run, syntax_error};
+
+#[test]
+fn test_if_else() {
+    let source = "var a = 1;
+if (a > 0) print \"positive\"; else print \"not positive\";
+if (a < 0) print \"negative\";
+if (nil) { print 1; } else if (a) { print 2; } else { print 3; }";
+    assert_eq!(run(source), Ok("positive\n2\n".to_owned()));
+}
+
+#[test]
+fn test_break() {
+    let source = "var i = 0;
+while (true) {
+  if (i == 3) break;
+  print i;
+  i = i + 1;
+}
+print \"done\";";
+    assert_eq!(run(source), Ok("0\n1\n2\ndone\n".to_owned()));
+}
+
+#[test]
+fn test_continue() {
+    let source = "var i = 0;
+while (i < 5) {
+  i = i + 1;
+  if (i == 2) continue;
+  if (i == 4) continue;
+  print i;
+}";
+    assert_eq!(run(source), Ok("1\n3\n5\n".to_owned()));
+}
+
+#[test]
+fn test_nested_loops() {
+    let source = "var i = 0;
+while (i < 2) {
+  var j = 0;
+  while (true) {
+    if (j == 2) break;
+    print i * 10 + j;
+    j = j + 1;
+  }
+  i = i + 1;
+}";
+    assert_eq!(run(source), Ok("0\n1\n10\n11\n".to_owned()));
+}
+
+#[test]
+fn test_scopes_unwound() {
+    // Each left block must restore the scope, or `x` would resolve to an inner one.
+    let source = "var x = \"outer\";
+var i = 0;
+while (i < 3) {
+  var x = \"loop\";
+  i = i + 1;
+  {
+    var x = \"inner\";
+    if (i == 1) continue;
+    break;
+  }
+}
+print x;
+print i;";
+    assert_eq!(run(source), Ok("outer\n2\n".to_owned()));
+}
+
+#[test]
+fn test_outside_loop_rejected() {
+    assert_eq!(
+        syntax_error("break;"),
+        "Can't use 'break' outside of a loop."
+    );
+    assert_eq!(
+        syntax_error("{ if (true) continue; }"),
+        "Can't use 'continue' outside of a loop."
+    );
+    assert_eq!(
+        syntax_error("while (true) {} break;"),
+        "Can't use 'break' outside of a loop."
+    );
+    assert_eq!(
+        syntax_error("while (true) break"),
+        "Expect ';' after 'break'."
+    );
+}
+
+#[test]
+fn test_printer_and_reader() {
+    let source = "while (a) { if (b) break; else continue; if (c) print 1; }";
+    let program = parse(source).parse().unwrap();
+    let printed = AstPrinter().fmt_stmt(&program);
+    assert_eq!(
+        printed,
+        "[(while a [(if b (break) (continue));(if c (print 1))])]"
+    );
+    let read = AstReader::read_program(&printed).unwrap();
+    assert_eq!(AstPrinter().fmt_stmt(&read), printed);
+}
+
+#[test]
+fn test_formatter() {
+    assert_eq!(
+        format_source("while(true){if(a){break;}else if(b)continue;else{print 1;}}").unwrap(),
+        "while (true) {\n  if (a) {\n    break;\n  } else if (b)\n    continue;\n  else {\n    print 1;\n  }\n}\n"
+    );
+    assert_eq!(
+        format_source("if (a) {}\nelse print 2; // two").unwrap(),
+        "if (a) {} else\n  print 2; // two\n"
+    );
+}
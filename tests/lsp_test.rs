@@ -0,0 +1,226 @@
+#![cfg(feature = "lsp")]
+
+use std::io::Cursor;
+
+use rlox::{
+    lsp::server::Server,
+    rpc::{read_message, write_message},
+};
+use serde_json::{Value, json};
+
+const URI: &str = "file:///main.lox";
+const SOURCE: &str =
+    "var count = 1;\n{\n  var count = count + 1;\n  print count;\n}\nprint count;\n";
+
+/// In-process client: queues messages, runs the server over them and
+/// collects what the server wrote back.
+struct Client {
+    input: Vec<u8>,
+    next_id: u64,
+}
+
+impl Client {
+    fn new() -> Self {
+        let mut client = Self {
+            input: vec![],
+            next_id: 0,
+        };
+        client.request("initialize", json!({ "capabilities": {} }));
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> u64 {
+        self.next_id += 1;
+        let message =
+            json!({ "jsonrpc": "2.0", "id": self.next_id, "method": method, "params": params });
+        write_message(&mut self.input, &message).unwrap();
+        self.next_id
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&mut self.input, &message).unwrap();
+    }
+
+    fn open(&mut self, text: &str) {
+        self.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": { "uri": URI, "languageId": "lox", "version": 1, "text": text } }),
+        );
+    }
+
+    fn at(&mut self, method: &str, line: u64, character: u64) -> u64 {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+                "context": { "includeDeclaration": true },
+            }),
+        )
+    }
+
+    /// Run the server until exit and return every message it sent.
+    fn finish(mut self) -> Vec<Value> {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        let mut output = vec![];
+        Server::new(&mut output)
+            .run(Cursor::new(self.input))
+            .unwrap();
+
+        let mut reader = Cursor::new(output);
+        let mut messages = vec![];
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+}
+
+fn response(messages: &[Value], id: u64) -> &Value {
+    &messages
+        .iter()
+        .find(|m| m["id"] == id)
+        .unwrap_or_else(|| panic!("no response for request {id}"))["result"]
+}
+
+fn range(line: u64, start: u64, end: u64) -> Value {
+    json!({
+        "start": { "line": line, "character": start },
+        "end": { "line": line, "character": end },
+    })
+}
+
+#[test]
+fn test_initialize_capabilities() {
+    let messages = Client::new().finish();
+    let capabilities = &response(&messages, 1)["capabilities"];
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(capabilities["referencesProvider"], true);
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(capabilities["documentSymbolProvider"], true);
+}
+
+#[test]
+fn test_publish_diagnostics() {
+    let mut client = Client::new();
+    client.open("var a = 1;\nvar = 2;\n");
+    let messages = client.finish();
+    let published = messages
+        .iter()
+        .find(|m| m["method"] == "textDocument/publishDiagnostics")
+        .unwrap();
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["range"], range(1, 0, 8));
+    assert!(
+        diagnostics[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Expect variable name.")
+    );
+}
+
+#[test]
+fn test_definition() {
+    let mut client = Client::new();
+    client.open(SOURCE);
+    // `count` in the initializer of the inner declaration refers to the outer one.
+    let outer = client.at("textDocument/definition", 2, 15);
+    // `count` printed inside the block refers to the inner one.
+    let inner = client.at("textDocument/definition", 3, 9);
+    let global = client.at("textDocument/definition", 5, 7);
+    let nothing = client.at("textDocument/definition", 3, 2);
+    let messages = client.finish();
+
+    assert_eq!(response(&messages, outer)["range"], range(0, 4, 9));
+    assert_eq!(response(&messages, inner)["range"], range(2, 6, 11));
+    assert_eq!(response(&messages, global)["range"], range(0, 4, 9));
+    assert_eq!(response(&messages, global)["uri"], URI);
+    assert_eq!(response(&messages, nothing), &Value::Null);
+}
+
+#[test]
+fn test_references() {
+    let mut client = Client::new();
+    client.open(SOURCE);
+    let id = client.at("textDocument/references", 0, 5);
+    let messages = client.finish();
+
+    let ranges: Vec<&Value> = response(&messages, id)
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| &location["range"])
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![&range(0, 4, 9), &range(2, 14, 19), &range(5, 6, 11)]
+    );
+}
+
+#[test]
+fn test_forward_global_reference() {
+    let mut client = Client::new();
+    client.open("test \"t\" { print later; }\nvar later = 1;\n");
+    let id = client.at("textDocument/definition", 0, 18);
+    let messages = client.finish();
+    assert_eq!(response(&messages, id)["range"], range(1, 4, 9));
+}
+
+#[test]
+fn test_hover() {
+    let mut client = Client::new();
+    client.open(SOURCE);
+    let id = client.at("textDocument/hover", 3, 9);
+    let messages = client.finish();
+    assert_eq!(
+        response(&messages, id)["contents"]["value"],
+        "```lox\nvar count = count + 1;\n```"
+    );
+}
+
+#[test]
+fn test_document_symbols() {
+    let mut client = Client::new();
+    client.open(SOURCE);
+    let id = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let messages = client.finish();
+
+    let symbols = response(&messages, id).as_array().unwrap();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0]["name"], "count");
+    assert_eq!(symbols[0]["kind"], 13);
+    assert_eq!(symbols[0]["selectionRange"], range(0, 4, 9));
+    assert_eq!(symbols[1]["range"], range(2, 0, 24));
+}
+
+#[test]
+fn test_did_change_reanalyzes() {
+    let mut client = Client::new();
+    client.open("var a = 1;");
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "var b = 1;\nprint b;" }],
+        }),
+    );
+    let id = client.at("textDocument/definition", 1, 6);
+    let messages = client.finish();
+    assert_eq!(response(&messages, id)["range"], range(0, 4, 5));
+}
+
+#[test]
+fn test_unknown_method() {
+    let mut client = Client::new();
+    let id = client.request("textDocument/rename", json!({}));
+    let messages = client.finish();
+    let error = &messages.iter().find(|m| m["id"] == id).unwrap()["error"];
+    assert_eq!(error["code"], -32601);
+}
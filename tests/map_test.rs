@@ -0,0 +1,161 @@
+use rlox::{
+    ast::pretty_printer::AstPrinter,
+    parser::Parser,
+    runner::format_source,
+    scanner::Scanner,
+    value::{LoxValue, Map, MapKey},
+};
+
+mod common;
+use common::{assert_runs_fail, run};
+
+#[test]
+fn test_literals_and_display() {
+    let source = "print {}; print {\"a\": 1, 2: [true], nil: {false: \"x\"}}; print type({});";
+    assert_eq!(
+        run(source),
+        Ok("{}\n{\"a\": 1, 2: [true], nil: {false: \"x\"}}\nmap\n".to_owned())
+    );
+}
+
+#[test]
+fn test_get_and_set() {
+    let source = "var m = {\"a\": 1};
+m[\"b\"] = 2;
+m[\"a\"] = 10;
+print m[\"a\"] + m[\"b\"];
+print m;
+m[1] = \"one\";
+print m[1.0];";
+    assert_eq!(
+        run(source),
+        Ok("12\n{\"a\": 10, \"b\": 2}\none\n".to_owned())
+    );
+}
+
+#[test]
+fn test_insertion_order() {
+    let source = "var m = {3: \"c\", 1: \"a\"};
+m[2] = \"b\";
+m[3] = \"C\";
+delete(m, 1);
+m[1] = \"A\";
+print keys(m);
+print values(m);";
+    assert_eq!(
+        run(source),
+        Ok("[3, 2, 1]\n[\"C\", \"b\", \"A\"]\n".to_owned())
+    );
+}
+
+#[test]
+fn test_key_rules() {
+    assert_eq!(
+        run("var m = {0: \"zero\"}; print m[-0]; print has(m, \"0\"); print has({nil: 1}, nil);"),
+        Ok("zero\nfalse\ntrue\n".to_owned())
+    );
+    assert_runs_fail(&[
+        ("var m = {}; m[0 / 0] = 1;", "can not divided by zero"),
+        (
+            "var m = {}; m[[]] = 1;",
+            "Map key must be a string, number, bool or nil, got list.",
+        ),
+        (
+            "var m = {}; m[{}];",
+            "Map key must be a string, number, bool or nil, got map.",
+        ),
+        ("{\"a\": 1}[\"b\"];", "Undefined key \"b\"."),
+    ]);
+
+    let nan = LoxValue::Number(f64::NAN);
+    assert_eq!(
+        MapKey::try_from(&nan).unwrap_err().to_string(),
+        "Runtime error: Map key can't be NaN."
+    );
+}
+
+#[test]
+fn test_natives() {
+    let source = "var m = {\"a\": 1, \"b\": 2};
+print len(m);
+print has(m, \"a\");
+print delete(m, \"a\");
+print delete(m, \"a\");
+print has(m, \"a\");
+print m;";
+    assert_eq!(
+        run(source),
+        Ok("2\ntrue\ntrue\nfalse\nfalse\n{\"b\": 2}\n".to_owned())
+    );
+    assert_runs_fail(&[
+        ("keys([]);", "keys() argument 1 must be a map, got list."),
+        (
+            "has({}, [1]);",
+            "Map key must be a string, number, bool or nil, got list.",
+        ),
+    ]);
+}
+
+#[test]
+fn test_reference_semantics() {
+    let source = "var a = {};
+var b = a;
+b[\"self\"] = a;
+print a;
+print a == b;
+print a == {};";
+    assert_eq!(
+        run(source),
+        Ok("{\"self\": {...}}\ntrue\nfalse\n".to_owned())
+    );
+}
+
+#[test]
+fn test_map_value() {
+    let mut map = Map::default();
+    map.insert(MapKey::String("a".to_owned()), LoxValue::Nil);
+    map.insert(MapKey::Bool(true), LoxValue::Int(1));
+    assert_eq!(map.len(), 2);
+    assert_eq!(
+        map.remove(&MapKey::String("a".to_owned())),
+        Some(LoxValue::Nil)
+    );
+    assert_eq!(map.get(&MapKey::Bool(true)), Some(&LoxValue::Int(1)));
+    assert_eq!(LoxValue::map(map).to_string(), "{true: 1}");
+}
+
+#[test]
+fn test_statement_disambiguation() {
+    // A `{` starting a statement is a map only when followed by a key and `:`.
+    assert_eq!(
+        run("var x = 3; {\"a\": 1}; {x: 2}; { print 1; } {} ({});"),
+        Ok("1\n".to_owned())
+    );
+    assert_eq!(
+        run("var x = \"k\"; print {x: 1, \"y\": {}}[\"k\"];"),
+        Ok("1\n".to_owned())
+    );
+    for source in ["{1 + 1: 2};", "print {\"a\" 1};", "print {\"a\": 1;"] {
+        let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse();
+        assert!(parser.had_error, "source: {source}");
+    }
+}
+
+#[test]
+fn test_printer_and_formatter() {
+    let tokens = Scanner::new("m[\"a\"] = {1: [2], k: m}".to_owned())
+        .scan_tokens()
+        .unwrap();
+    let expr = Parser::new(tokens).parse_expr().unwrap();
+    assert_eq!(
+        AstPrinter().fmt(&expr),
+        "(index= m \"a\" (map 1 (list 2) k m))"
+    );
+
+    assert_eq!(
+        format_source("print {\"a\":1,b:(2)};({})[\"a\"]=1;{x:1};").unwrap(),
+        "print {\"a\": 1, b: 2};\n({}[\"a\"] = 1);\n({x: 1});\n"
+    );
+}
@@ -0,0 +1,49 @@
+mod common;
+use common::{assert_evals, assert_fails};
+
+#[test]
+fn test_str() {
+    assert_evals(&[
+        ("str(1.5) + \"!\"", "1.5!"),
+        ("str(nil)", "nil"),
+        ("str(true)", "true"),
+        ("str(\"lox\")", "lox"),
+        ("str(clock)", "<native fn clock>"),
+    ]);
+}
+
+#[test]
+fn test_num() {
+    assert_evals(&[
+        ("num(\"42\") + 1", "43"),
+        ("num(\" -1.5 \")", "-1.5"),
+        ("num(\"1e3\")", "1000.0"),
+        ("num(7)", "7"),
+        ("num(\"abc\")", "nil"),
+        ("num(\"inf\")", "nil"),
+        ("num(\"NaN\")", "nil"),
+        ("num(\"\")", "nil"),
+    ]);
+    assert_fails(&[
+        (
+            "num(true)",
+            "num() argument 1 must be a string or number, got bool.",
+        ),
+        (
+            "num(nil)",
+            "num() argument 1 must be a string or number, got nil.",
+        ),
+    ]);
+}
+
+#[test]
+fn test_type() {
+    assert_evals(&[
+        ("type(1)", "int"),
+        ("type(1.0)", "float"),
+        ("type(\"1\")", "string"),
+        ("type(false)", "bool"),
+        ("type(nil)", "nil"),
+        ("type(type)", "function"),
+    ]);
+}
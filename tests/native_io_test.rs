@@ -0,0 +1,161 @@
+use std::{fs, io::Cursor, path::Path, process::Command};
+
+use rlox::{
+    interpreter::Interpreter,
+    native::{Capabilities, Capability},
+    parser::Parser,
+    scanner::Scanner,
+};
+use tempfile::tempdir;
+
+mod common;
+use common::{Buffer, run_in};
+
+/// Interpreter reading `input`, with its output and error output captured.
+fn interpreter(input: &str) -> (Interpreter, Buffer, Buffer) {
+    let (output, error_output) = (Buffer::default(), Buffer::default());
+    let mut interpreter = Interpreter::new();
+    interpreter.set_input(Box::new(Cursor::new(input.to_owned())));
+    interpreter.set_output(Box::new(output.clone()));
+    interpreter.set_error_output(Box::new(error_output.clone()));
+    (interpreter, output, error_output)
+}
+
+/// Lox string literal of a path.
+fn literal(path: &Path) -> String {
+    format!("\"{}\"", path.display())
+}
+
+#[test]
+fn test_read_line() {
+    let (mut interpreter, output, _) = interpreter("first\r\nsecond\nlast");
+    let source = "print readLine(); print readLine(); print readLine(); print readLine();";
+    assert_eq!(run_in(&mut interpreter, source), Ok(()));
+    assert_eq!(output.take(), "first\nsecond\nlast\nnil\n");
+}
+
+#[test]
+fn test_eprint_and_uncaught_errors() {
+    let (mut interpreter, output, error_output) = interpreter("");
+    assert_eq!(
+        run_in(&mut interpreter, "eprint(\"oops\"); print 1;"),
+        Ok(())
+    );
+    assert_eq!(error_output.take(), "oops\n");
+    assert_eq!(output.take(), "1\n");
+
+    let tokens = Scanner::new("print -nil;".to_owned())
+        .scan_tokens()
+        .unwrap();
+    interpreter
+        .interpret(Parser::new(tokens).parse().unwrap())
+        .unwrap();
+    assert_eq!(
+        error_output.take(),
+        "Runtime error: Operand must be a number\n"
+    );
+}
+
+#[test]
+fn test_files() {
+    let dir = tempdir().unwrap();
+    let path = literal(&dir.path().join("notes.txt"));
+    let (mut interpreter, output, _) = interpreter("");
+    let source = format!(
+        "print fileExists({path});
+writeFile({path}, \"a\\n\");
+appendFile({path}, \"b\\n\");
+print fileExists({path});
+print readFile({path});
+writeFile({path}, \"c\");
+print readFile({path});"
+    );
+    assert_eq!(run_in(&mut interpreter, &source), Ok(()));
+    assert_eq!(output.take(), "false\ntrue\na\nb\n\nc\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("notes.txt")).unwrap(),
+        "c"
+    );
+}
+
+#[test]
+fn test_append_creates_file() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("log.txt");
+    let (mut interpreter, _, _) = interpreter("");
+    let source = format!("appendFile({}, \"x\");", literal(&path));
+    assert_eq!(run_in(&mut interpreter, &source), Ok(()));
+    assert_eq!(fs::read_to_string(path).unwrap(), "x");
+}
+
+#[test]
+fn test_file_errors() {
+    let dir = tempdir().unwrap();
+    let missing = dir.path().join("missing.txt");
+    let (mut interpreter, _, _) = interpreter("");
+    let error = run_in(
+        &mut interpreter,
+        &format!("readFile({});", literal(&missing)),
+    )
+    .unwrap_err();
+    assert!(
+        error.starts_with(&format!(
+            "Runtime error: readFile() failed on '{}':",
+            missing.display()
+        )),
+        "{error}"
+    );
+    assert_eq!(
+        run_in(&mut interpreter, "writeFile(\"x\", 1);"),
+        Err("Runtime error: writeFile() argument 2 must be a string, got int.".to_owned())
+    );
+}
+
+#[test]
+fn test_args() {
+    let (mut interpreter, output, _) = interpreter("");
+    interpreter.set_args(vec!["one".to_owned(), "two".to_owned()]);
+    assert_eq!(
+        run_in(
+            &mut interpreter,
+            "print args(); print args(0); print args(2);"
+        ),
+        Ok(())
+    );
+    assert_eq!(output.take(), "[\"one\", \"two\"]\none\nnil\n");
+}
+
+#[test]
+fn test_denied_io_and_fs() {
+    let capabilities = Capabilities::all()
+        .without(Capability::Io)
+        .without(Capability::Fs);
+    let mut interpreter = Interpreter::with_capabilities(capabilities);
+    assert_eq!(
+        run_in(&mut interpreter, "readLine();"),
+        Err("Runtime error: readLine() requires the 'io' capability, which is denied.".to_owned())
+    );
+    assert_eq!(
+        run_in(&mut interpreter, "fileExists(\".\");"),
+        Err(
+            "Runtime error: fileExists() requires the 'fs' capability, which is denied.".to_owned()
+        )
+    );
+}
+
+#[test]
+fn test_cli_passes_script_arguments() {
+    let dir = tempdir().unwrap();
+    let script = dir.path().join("echo.lox");
+    fs::write(&script, "print args();\nprint args(0);\nprint args(1);\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .arg(&script)
+        .args(["--flag", "value"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "[\"--flag\", \"value\"]\n--flag\nvalue\n"
+    );
+}
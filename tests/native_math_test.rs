@@ -0,0 +1,50 @@
+mod common;
+use common::{assert_evals, assert_fails};
+
+#[test]
+fn test_rounding() {
+    assert_evals(&[
+        ("floor(1.7)", "1"),
+        ("floor(-1.2)", "-2"),
+        ("ceil(1.2)", "2"),
+        ("ceil(-1.7)", "-1"),
+        ("abs(-3)", "3"),
+        ("abs(3)", "3"),
+    ]);
+}
+
+#[test]
+fn test_powers() {
+    assert_evals(&[
+        ("sqrt(16)", "4.0"),
+        ("sqrt(-1)", "NaN"),
+        ("pow(2, 10)", "1024.0"),
+        ("pow(4, 0.5)", "2.0"),
+    ]);
+}
+
+#[test]
+fn test_min_max() {
+    assert_evals(&[
+        ("min(3)", "3"),
+        ("min(3, 1, 2)", "1"),
+        ("max(3, 1, 2)", "3"),
+        ("max(-1, -5)", "-1"),
+    ]);
+}
+
+#[test]
+fn test_wrong_argument_types() {
+    assert_fails(&[
+        (
+            "floor(\"1\")",
+            "floor() argument 1 must be a number, got string.",
+        ),
+        ("pow(2, nil)", "pow() argument 2 must be a number, got nil."),
+        (
+            "max(1, 2, \"3\")",
+            "max() argument 3 must be a number, got string.",
+        ),
+        ("min()", "min expected 1 to 255 arguments but got 0."),
+    ]);
+}
@@ -0,0 +1,74 @@
+mod common;
+use common::{assert_evals, assert_fails};
+
+#[test]
+fn test_len() {
+    assert_evals(&[
+        ("len(\"\")", "0"),
+        ("len(\"lox\")", "3"),
+        ("len(\"héllo\")", "5"),
+    ]);
+}
+
+#[test]
+fn test_substr() {
+    assert_evals(&[
+        ("substr(\"hello\", 1)", "ello"),
+        ("substr(\"hello\", 1, 3)", "ell"),
+        ("substr(\"hello\", 2, 100)", "llo"),
+        ("substr(\"hello\", 5)", ""),
+        ("substr(\"héllo\", 1, 1)", "é"),
+    ]);
+    assert_fails(&[
+        (
+            "substr(\"hello\", 6)",
+            "substr() start 6 is out of range for a string of length 5.",
+        ),
+        (
+            "substr(\"hello\", -1)",
+            "substr() argument 2 must be a non negative integer, got int.",
+        ),
+        (
+            "substr(\"hello\", 1.5)",
+            "substr() argument 2 must be a non negative integer, got float.",
+        ),
+    ]);
+}
+
+#[test]
+fn test_index_of() {
+    assert_evals(&[
+        ("indexOf(\"hello\", \"l\")", "2"),
+        ("indexOf(\"hello\", \"\")", "0"),
+        ("indexOf(\"hello\", \"z\")", "-1"),
+        ("indexOf(\"héllo\", \"l\")", "2"),
+    ]);
+}
+
+#[test]
+fn test_case_and_trim() {
+    assert_evals(&[
+        ("upper(\"Lox!\")", "LOX!"),
+        ("lower(\"Lox!\")", "lox!"),
+        ("trim(\"  lox \\t\")", "lox"),
+    ]);
+}
+
+#[test]
+fn test_wrong_argument_types() {
+    assert_fails(&[
+        (
+            "len(1)",
+            "len() argument 1 must be a string, list or map, got int.",
+        ),
+        (
+            "upper(nil)",
+            "upper() argument 1 must be a string, got nil.",
+        ),
+        (
+            "indexOf(\"a\", true)",
+            "indexOf() argument 2 must be a string, got bool.",
+        ),
+        ("trim()", "trim expected 1 arguments but got 0."),
+    ]);
+}
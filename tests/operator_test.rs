@@ -0,0 +1,99 @@
+use rlox::{ast::reader::AstReader, interpreter::Interpreter, runner::format_source};
+
+mod common;
+use common::eval;
+
+#[test]
+fn test_floored_division_identity() {
+    for (a, b) in [(7, 3), (-7, 3), (7, -3), (-7, -3), (0, 5), (9, 3)] {
+        let source = format!("({a} ~/ {b}) * {b} + {a} % {b} == {a}");
+        assert_eq!(eval(&source), Ok("true".to_owned()), "{source}");
+    }
+}
+
+#[test]
+fn test_runtime_errors() {
+    let cases = [
+        ("1 % 0", "Modulo by zero."),
+        ("1 ~/ 0", "Integer division by zero."),
+        ("\"a\" % 2", "Operands must be two numbers."),
+        ("2 ** nil", "Operands must be two numbers."),
+        ("[] ~/ 1", "Operands must be two numbers."),
+        (
+            "1.5 & 1",
+            "Operands of '&' must be 64-bit integers, got 1.5.",
+        ),
+        (
+            "1 | \"a\"",
+            "Operands of '|' must be 64-bit integers, got \"a\".",
+        ),
+        ("~nil", "Operands of '~' must be 64-bit integers, got nil."),
+        (
+            "2.0 ** 63 ^ 1",
+            "Operands of '^' must be 64-bit integers, got 9223372036854775808.0.",
+        ),
+        ("1 << 64", "Shift amount must be between 0 and 63, got 64."),
+        ("8 >> -1", "Shift amount must be between 0 and 63, got -1."),
+    ];
+    for (source, message) in cases {
+        assert_eq!(
+            eval(source),
+            Err(format!("Runtime error: {message}")),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn test_unsupported_operator_is_an_error() {
+    // The parser never builds such a node, but a read AST can hold one.
+    let expr = AstReader::read_expr("(! 1 2)").unwrap();
+    assert_eq!(
+        Interpreter::new().evaluate(&expr).unwrap_err().to_string(),
+        "Runtime error: Unsupported binary operator '!'."
+    );
+}
+
+#[test]
+fn test_slash_slash_stays_a_comment() {
+    assert_eq!(
+        format_source("print 7 ~/ 2; // not division\n").unwrap(),
+        "print 7 ~/ 2; // not division\n"
+    );
+}
+
+#[test]
+fn test_formatter_parentheses() {
+    assert_eq!(
+        format_source(
+            "print -(2**2); print (-2)**2; print (2**3)**2; print 2**(3**2); print 2**(-1);"
+        )
+        .unwrap(),
+        "print -2 ** 2;\nprint (-2) ** 2;\nprint (2 ** 3) ** 2;\nprint 2 ** 3 ** 2;\nprint 2 ** -1;\n"
+    );
+    assert_eq!(
+        format_source("print (a%b)*(c~/d) % (e*f);").unwrap(),
+        "print a % b * (c ~/ d) % (e * f);\n"
+    );
+}
+
+#[test]
+fn test_integer_semantics() {
+    let cases = [
+        ("-(2.0 ** 63) >> 63", "-1"),
+        ("1 << 63 == -(2.0 ** 63)", "true"),
+        ("(1 << 62) << 2", "0"),
+        ("~0 & 255", "255"),
+    ];
+    for (source, value) in cases {
+        assert_eq!(eval(source), Ok(value.to_owned()), "{source}");
+    }
+}
+
+#[test]
+fn test_formatter_bitwise_parentheses() {
+    assert_eq!(
+        format_source("print (a|b)&c; print a|(b&c); print (1<<2)+3; print ~(a^b);").unwrap(),
+        "print (a | b) & c;\nprint a | b & c;\nprint (1 << 2) + 3;\nprint ~(a ^ b);\n"
+    );
+}
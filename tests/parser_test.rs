@@ -10,11 +10,25 @@ fn preprae_test_case() -> Vec<(&'static str, &'static str)> {
             "(-1 + 2) * 3 == 6 / 2 == true != false != nil",
             "(!= (!= (== (== (* (group (+ (- 1) 2)) 3) (/ 6 2)) true) false) nil)",
         ),
-        ("\"hello\" + \"lox\"", "(+ hello lox)"),
+        ("\"hello\" + \"lox\"", "(+ \"hello\" \"lox\")"),
         (
             "1 + 2 == 3 / 4 ? 2 - 1 : 5 + 6 * 7",
             "(? (== (+ 1 2) (/ 3 4)) (- 2 1) (+ 5 (* 6 7)))",
         ),
+        ("-2 ** 2", "(- (** 2 2))"),
+        ("a += b -= 2 * c", "(+= a (-= b (* 2 c)))"),
+        ("xs[i] /= 2", "(/= (index xs i) 2)"),
+        ("-++a ** b--", "(- (** (++ a) (post-- b)))"),
+        (
+            "xs[0]++ + --f()[1]",
+            "(+ (post++ (index xs 0)) (-- (index (call f) 1)))",
+        ),
+        ("2 ** 3 ** -x", "(** 2 (** 3 (- x)))"),
+        ("a % b ~/ c * d ** e", "(* (~/ (% a b) c) (** d e))"),
+        ("f(x) ** 2 + 1", "(+ (** (call f x) 2) 1)"),
+        ("a | b ^ c & d << 1 + e", "(| a (^ b (& c (<< d (+ 1 e)))))"),
+        ("~a >> 2 == b & 1", "(== (>> (~ a) 2) (& b 1))"),
+        ("0..1 << n", "(.. 0 (<< 1 n))"),
         (
             "1 == 2 ? 3 : 4 == 5 ? 6 : 7",
             "(? (== 1 2) 3 (? (== 4 5) 6 7))",
@@ -34,3 +48,13 @@ fn test_parser() {
         assert_eq!(expected, &printer.fmt(&expr));
     });
 }
+
+#[test]
+fn test_synchronize_skips_to_next_statement() {
+    let tokens = Scanner::new("print 1 2 3; print 4;".to_owned())
+        .scan_tokens()
+        .unwrap();
+    let mut parser = Parser::new(tokens);
+    parser.parse();
+    assert!(parser.had_error);
+}
@@ -9,7 +9,7 @@ fn test_pretty_printer() {
         left: Box::new(Expr::Unary {
             operator: Token::new(TokenType::Minus, "-".to_string(), LiteralType::Nil, 1),
             right: Box::new(Expr::Literal {
-                value: LiteralType::Number(123.0),
+                value: LiteralType::Int(123),
             }),
         }),
         operator: Token::new(TokenType::Star, "*".to_string(), LiteralType::Nil, 1),
@@ -29,29 +29,29 @@ fn test_ternary_operator() {
     let expr = Expr::Ternary {
         condition: Box::new(Expr::Binary {
             left: Box::new(Expr::Literal {
-                value: LiteralType::Number(1.0),
+                value: LiteralType::Int(1),
             }),
             operator: Token::new(TokenType::EqualEqual, "==".to_string(), LiteralType::Nil, 1),
             right: Box::new(Expr::Literal {
-                value: LiteralType::Number(2.0),
+                value: LiteralType::Int(2),
             }),
         }),
         truepart: Box::new(Expr::Binary {
             left: Box::new(Expr::Literal {
-                value: LiteralType::Number(1.0),
+                value: LiteralType::Int(1),
             }),
             operator: Token::new(TokenType::Plus, "+".to_string(), LiteralType::Nil, 1),
             right: Box::new(Expr::Literal {
-                value: LiteralType::Number(2.0),
+                value: LiteralType::Int(2),
             }),
         }),
         falsepart: Box::new(Expr::Binary {
             left: Box::new(Expr::Literal {
-                value: LiteralType::Number(3.0),
+                value: LiteralType::Int(3),
             }),
             operator: Token::new(TokenType::Star, "*".to_string(), LiteralType::Nil, 1),
             right: Box::new(Expr::Literal {
-                value: LiteralType::Number(4.0),
+                value: LiteralType::Int(4),
             }),
         }),
     };
@@ -0,0 +1,125 @@
+use std::{cell::RefCell, fs, io, process::Command, rc::Rc};
+
+use rlox::{
+    interpreter::Interpreter,
+    parser::Parser,
+    profile::{Profile, Profiler},
+    scanner::Scanner,
+};
+use tempfile::tempdir;
+
+const SOURCE: &str = "var a = 1; a = a + 1;
+{
+  var b = a;
+  print b;
+}
+print a;
+";
+
+fn profile(source: &str) -> Profile {
+    let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let profile = Rc::new(RefCell::new(Profile::default()));
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(io::sink()));
+    interpreter.set_hook(Box::new(Profiler::new(Rc::clone(&profile))));
+    interpreter.execute(&program).unwrap();
+    drop(interpreter);
+    Rc::into_inner(profile).unwrap().into_inner()
+}
+
+#[test]
+fn test_count_by_line_and_kind() {
+    let profile = profile(SOURCE);
+    let counts: Vec<(usize, u64)> = profile
+        .lines
+        .iter()
+        .map(|(line, stats)| (*line, stats.count))
+        .collect();
+    assert_eq!(counts, [(1, 2), (2, 1), (3, 1), (4, 1), (6, 1)]);
+
+    let counts: Vec<(&str, u64)> = profile
+        .kinds
+        .iter()
+        .map(|(kind, stats)| (*kind, stats.count))
+        .collect();
+    assert_eq!(
+        counts,
+        [("Block", 1), ("Expression", 1), ("Print", 2), ("Var", 2)]
+    );
+}
+
+#[test]
+fn test_nested_time() {
+    let profile = profile(SOURCE);
+    for stats in profile.lines.values() {
+        assert!(stats.own <= stats.total);
+    }
+    let block = profile.lines[&2];
+    let nested = profile.lines[&3].total + profile.lines[&4].total;
+    assert!(block.total >= nested);
+    assert_eq!(block.own, block.total - nested);
+}
+
+#[test]
+fn test_folded_stacks() {
+    let profile = profile(SOURCE);
+    let stacks: Vec<&str> = profile.stacks.keys().map(String::as_str).collect();
+    assert_eq!(
+        stacks,
+        [
+            "Block:2",
+            "Block:2;Print:4",
+            "Block:2;Var:3",
+            "Expression:1",
+            "Print:6",
+            "Var:1"
+        ]
+    );
+    for line in profile.folded().lines() {
+        let (stack, weight) = line.rsplit_once(' ').unwrap();
+        assert!(profile.stacks.contains_key(stack));
+        assert!(weight.parse::<u128>().is_ok());
+    }
+}
+
+#[test]
+fn test_report_sorted_by_own_time() {
+    let report = profile(SOURCE).report();
+    let mut sections = report.split("\n\n");
+    let lines = sections.next().unwrap();
+    assert!(lines.starts_with("Hot spots by line:\n"));
+    let own: Vec<f64> = lines
+        .lines()
+        .skip(2)
+        .map(|row| row.split_whitespace().nth(2).unwrap().parse().unwrap())
+        .collect();
+    assert_eq!(own.len(), 5);
+    assert!(own.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(
+        sections
+            .next()
+            .unwrap()
+            .starts_with("Hot spots by statement kind:\n")
+    );
+}
+
+#[test]
+fn test_profile_cli() {
+    let dir = tempdir().unwrap();
+    let script = dir.path().join("script.lox");
+    let folded = dir.path().join("stacks.folded");
+    fs::write(&script, SOURCE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .arg("--profile")
+        .arg(format!("--folded={}", folded.display()))
+        .arg(&script)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n2\n");
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Hot spots by line:\n"));
+    assert_eq!(fs::read_to_string(folded).unwrap().lines().count(), 6);
+}
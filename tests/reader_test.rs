@@ -0,0 +1,223 @@
+use std::rc::Rc;
+
+use rlox::{
+    ast::{expr::Expr, pretty_printer::AstPrinter, reader::AstReader, stmt::Stmt},
+    parser::Parser,
+    scanner::Scanner,
+    token::{LiteralType, Token, TokenType},
+};
+
+/// Small deterministic xorshift generator, enough to drive the property tests.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+}
+
+const NAMES: [&str; 5] = ["a", "b", "foo", "bar_1", "_x"];
+const STRINGS: [&str; 5] = [
+    "",
+    "hello lox",
+    "with \"quotes\"",
+    "back\\slash",
+    "tab\tnew\nline;]",
+];
+const BINARY: [(TokenType, &str); 10] = [
+    (TokenType::Plus, "+"),
+    (TokenType::Minus, "-"),
+    (TokenType::Star, "*"),
+    (TokenType::Slash, "/"),
+    (TokenType::EqualEqual, "=="),
+    (TokenType::BangEqual, "!="),
+    (TokenType::Less, "<"),
+    (TokenType::LessEqual, "<="),
+    (TokenType::Greater, ">"),
+    (TokenType::GreaterEqual, ">="),
+];
+
+fn token(token_type: TokenType, lexeme: &str) -> Token {
+    Token::new(token_type, lexeme.to_owned(), LiteralType::Nil, 0)
+}
+
+fn gen_expr(rng: &mut Rng, depth: usize) -> Expr {
+    let choice = if depth == 0 {
+        rng.below(3)
+    } else {
+        rng.below(14)
+    };
+    let sub = |rng: &mut Rng| Box::new(gen_expr(rng, depth - 1));
+    match choice {
+        0 => Expr::Literal {
+            value: match rng.below(5) {
+                0 => LiteralType::Number((rng.below(200_000) as f64 - 100_000.0) / 100.0),
+                1 => LiteralType::String(rng.pick(&STRINGS).to_string()),
+                2 => LiteralType::Bool(rng.below(2) == 0),
+                _ => LiteralType::Nil,
+            },
+        },
+        1 | 2 => Expr::Variable {
+            name: token(TokenType::Identifier, rng.pick(&NAMES)),
+        },
+        3 => Expr::Assignment {
+            name: token(TokenType::Identifier, rng.pick(&NAMES)),
+            value: sub(rng),
+        },
+        4 => {
+            let (token_type, lexeme) = rng.pick(&BINARY);
+            Expr::Binary {
+                left: sub(rng),
+                operator: token(token_type, lexeme),
+                right: sub(rng),
+            }
+        }
+        5 => Expr::Grouping {
+            expression: sub(rng),
+        },
+        6 => {
+            let (token_type, lexeme) = rng.pick(&[(TokenType::Minus, "-"), (TokenType::Bang, "!")]);
+            Expr::Unary {
+                operator: token(token_type, lexeme),
+                right: sub(rng),
+            }
+        }
+        7 => Expr::Ternary {
+            condition: sub(rng),
+            truepart: sub(rng),
+            falsepart: sub(rng),
+        },
+        8 => Expr::Call {
+            callee: sub(rng),
+            paren: token(TokenType::RightParen, ")"),
+            arguments: (0..rng.below(3))
+                .map(|_| gen_expr(rng, depth - 1))
+                .collect(),
+        },
+        9 => Expr::List {
+            bracket: token(TokenType::LeftBracket, "["),
+            elements: (0..rng.below(3))
+                .map(|_| gen_expr(rng, depth - 1))
+                .collect(),
+        },
+        11 => Expr::Map {
+            brace: token(TokenType::LeftBrace, "{"),
+            entries: (0..rng.below(3))
+                .map(|_| (gen_expr(rng, depth - 1), gen_expr(rng, depth - 1)))
+                .collect(),
+        },
+        12 => {
+            let keyword = token(TokenType::Arrow, "=>");
+            Expr::Lambda {
+                params: (0..rng.below(3))
+                    .map(|_| token(TokenType::Identifier, rng.pick(&NAMES)))
+                    .collect(),
+                body: Rc::new(Stmt::Return(keyword.clone(), Some(*sub(rng)))),
+                keyword,
+            }
+        }
+        10 => Expr::Index {
+            object: sub(rng),
+            bracket: token(TokenType::LeftBracket, "["),
+            index: sub(rng),
+        },
+        _ => Expr::IndexSet {
+            object: sub(rng),
+            bracket: token(TokenType::LeftBracket, "["),
+            index: sub(rng),
+            value: sub(rng),
+        },
+    }
+}
+
+fn gen_stmt(rng: &mut Rng, depth: usize) -> Stmt {
+    match if depth == 0 {
+        rng.below(3)
+    } else {
+        rng.below(4)
+    } {
+        0 => Stmt::Expression(gen_expr(rng, 3), 0),
+        1 => Stmt::Print(gen_expr(rng, 3), 0),
+        2 => Stmt::Var(
+            token(TokenType::Identifier, rng.pick(&NAMES)),
+            (rng.below(2) == 0).then(|| gen_expr(rng, 3)),
+        ),
+        _ => Stmt::Block(
+            (0..rng.below(4))
+                .map(|_| gen_stmt(rng, depth - 1))
+                .collect(),
+            0,
+            0,
+        ),
+    }
+}
+
+#[test]
+fn test_expr_round_trip() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut printer = AstPrinter();
+    for _ in 0..2000 {
+        let expr = gen_expr(&mut rng, 4);
+        let printed = printer.fmt(&expr);
+        let read = AstReader::read_expr(&printed)
+            .unwrap_or_else(|e| panic!("failed to read {printed}: {e}"));
+        assert_eq!(read, expr, "printed: {printed}");
+    }
+}
+
+#[test]
+fn test_program_round_trip() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut printer = AstPrinter();
+    for _ in 0..500 {
+        let program = Stmt::Program((0..rng.below(5)).map(|_| gen_stmt(&mut rng, 2)).collect());
+        let printed = printer.fmt_stmt(&program);
+        let read = AstReader::read_program(&printed)
+            .unwrap_or_else(|e| panic!("failed to read {printed}: {e}"));
+        assert_eq!(read, program, "printed: {printed}");
+    }
+}
+
+#[test]
+fn test_parsed_source_round_trip() {
+    let source = r#"
+        var a = 1;
+        { var b = "x;]" + a; print -b; }
+        test "works" { assert(a == 1, "a is one"); }
+        a = a > 2 ? f(1, (2)) : !nil;
+        var xs = [1, [a], "]"]; xs[0] = xs[1][0];
+        {"k": {}}["k"][1] = {a: xs};
+        var f = fun (a, b) { while (a) { if (b) break; else continue; } return; };
+        var g = (x) => fun () { return x; };
+        for (var i in 0..len(xs) - 1) { print i; }
+        print -2 ** i ** 2 % 3 ~/ 2;
+        print ~a | b ^ c & d << 1 >> e;
+        xs[i++] *= --a; a -= xs[0]--; a /= 2; xs[0] += ++a;
+    "#;
+    let mut scanner = Scanner::new(source.to_owned());
+    let mut parser = Parser::new(scanner.scan_tokens().unwrap());
+    let program = parser.parse().unwrap();
+    let mut printer = AstPrinter();
+    let printed = printer.fmt_stmt(&program);
+    let read = AstReader::read_program(&printed).unwrap();
+    assert_eq!(printer.fmt_stmt(&read), printed);
+}
+
+#[test]
+fn test_read_errors() {
+    for source in ["(+ 1", "(@ 1 2)", "(group)", "1 2", "\"open", "(= 1 2)"] {
+        assert!(AstReader::read_expr(source).is_err(), "source: {source}");
+    }
+    assert!(AstReader::read_program("[(print 1) (print 2)]").is_err());
+}
@@ -0,0 +1,64 @@
+use rlox::{interpreter::Interpreter, runner::run_repl};
+
+mod common;
+use common::Buffer;
+
+/// Feed lines to a REPL session, returning what was printed and what was reported.
+fn repl(lines: &str) -> (String, String, Interpreter) {
+    let (output, errors) = (Buffer::default(), Buffer::default());
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(output.clone()));
+    interpreter.set_error_output(Box::new(errors.clone()));
+    run_repl(lines.as_bytes(), &mut interpreter).unwrap();
+    (output.take(), errors.take(), interpreter)
+}
+
+#[test]
+fn test_globals_visible_after_failed_block() {
+    let (output, errors, interpreter) = repl(
+        "var a = \"global\";
+{ var a = \"shadow\"; print a; print nil + 1; }
+print a;
+",
+    );
+    assert_eq!(output, "shadow\nglobal\n");
+    assert_eq!(
+        errors,
+        "Runtime error: Operands must be two numbers or strings.\n"
+    );
+    assert_eq!(interpreter.environment.depth(), 0);
+}
+
+#[test]
+fn test_nested_blocks_unwound() {
+    let (output, _, interpreter) = repl(
+        "var a = 1;
+{ var b = 2; { var c = 3; undefined; } }
+print a;
+b = 4;
+var i = 0;
+while (true) { { var a = 2; i = i + 1; if (i == 2) i(); } }
+print a;
+print i;
+",
+    );
+    assert_eq!(output, "1\n1\n2\n");
+    assert_eq!(interpreter.environment.depth(), 0);
+}
+
+#[test]
+fn test_definitions_after_failed_block_are_global() {
+    let (output, errors, _) = repl(
+        "{ var x = 1; x(); }
+var y = 2;
+print y;
+{ print y; }
+print x;
+",
+    );
+    assert_eq!(output, "2\n2\n");
+    assert_eq!(
+        errors,
+        "Runtime error: Can only call functions.\nRuntime error: Undefined variable: x.\n"
+    );
+}
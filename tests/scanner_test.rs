@@ -49,7 +49,7 @@ fn test_numbers_with_leading_zeros() {
     let tokens = scan("007 0.123");
     assert_eq!(tokens.len(), 3);
     assert_eq!(tokens[0].token_type, TokenType::Number);
-    assert_eq!(tokens[0].literal, LiteralType::Number(7.0));
+    assert_eq!(tokens[0].literal, LiteralType::Int(7));
     assert_eq!(tokens[1].token_type, TokenType::Number);
     assert_eq!(tokens[1].literal, LiteralType::Number(0.123));
 }
@@ -162,3 +162,68 @@ fn test_ternary_operator() {
         ]
     );
 }
+
+#[test]
+fn test_comments_collected() {
+    let mut scanner = Scanner::new("var a; // first\n// second  \nprint a;".to_owned());
+    scanner.scan_tokens().unwrap();
+    let comments: Vec<(&str, usize)> = scanner
+        .comments
+        .iter()
+        .map(|c| (c.text.as_str(), c.line))
+        .collect();
+    assert_eq!(comments, vec![("// first", 1), ("// second", 2)]);
+}
+
+#[test]
+fn test_compound_assignment_operators() {
+    let tokens = scan("a += b -= c *= d /= e++ - --f // c");
+    let token_types: Vec<TokenType> = tokens.iter().map(|t| t.token_type).collect();
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::Identifier,
+            TokenType::PlusEqual,
+            TokenType::Identifier,
+            TokenType::MinusEqual,
+            TokenType::Identifier,
+            TokenType::StarEqual,
+            TokenType::Identifier,
+            TokenType::SlashEqual,
+            TokenType::Identifier,
+            TokenType::PlusPlus,
+            TokenType::Minus,
+            TokenType::MinusMinus,
+            TokenType::Identifier,
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_bitwise_operators() {
+    let tokens = scan("a & b | c ^ ~d << e >> f <= g ~/ h");
+    let token_types: Vec<TokenType> = tokens.iter().map(|t| t.token_type).collect();
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::Identifier,
+            TokenType::Ampersand,
+            TokenType::Identifier,
+            TokenType::Pipe,
+            TokenType::Identifier,
+            TokenType::Caret,
+            TokenType::Tilde,
+            TokenType::Identifier,
+            TokenType::LessLess,
+            TokenType::Identifier,
+            TokenType::GreaterGreater,
+            TokenType::Identifier,
+            TokenType::LessEqual,
+            TokenType::Identifier,
+            TokenType::TildeSlash,
+            TokenType::Identifier,
+            TokenType::Eof,
+        ]
+    );
+}
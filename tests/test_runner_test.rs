@@ -0,0 +1,160 @@
+use std::fs;
+
+use rlox::test_runner::{ReportFormat, discover, render, run_source};
+
+const SUITE: &str = r#"
+var base = 40;
+
+test "addition" {
+    assert(base + 2 == 42, "base should add up");
+}
+
+test "failing assertion" {
+    assert(base == 0, "base is not zero");
+}
+
+test "isolated state" {
+    base = base + 1;
+    assert(base == 41);
+}
+
+test "isolated state again" {
+    base = base + 1;
+    assert(base == 41);
+}
+"#;
+
+#[test]
+fn test_run_source() {
+    let results = run_source("suite_test.lox", SUITE);
+    let outcomes: Vec<(&str, bool)> = results
+        .iter()
+        .map(|r| (r.name.as_str(), r.passed()))
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            ("addition", true),
+            ("failing assertion", false),
+            ("isolated state", true),
+            ("isolated state again", true),
+        ]
+    );
+    assert!(
+        results[1]
+            .failure
+            .as_ref()
+            .unwrap()
+            .contains("base is not zero")
+    );
+}
+
+#[test]
+fn test_test_blocks_skipped_outside_runner() {
+    let results = run_source("empty_test.lox", "var test = 1; print test;");
+    assert!(results.is_empty());
+}
+
+#[test]
+fn test_parse_error_reported_as_failure() {
+    let results = run_source("broken_test.lox", "test \"broken\" { assert(; }");
+    assert_eq!(results.len(), 1);
+    assert!(!results[0].passed());
+}
+
+#[test]
+fn test_tap_report() {
+    let results = run_source("suite_test.lox", SUITE);
+    let report = render(&results, ReportFormat::Tap);
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines[0], "TAP version 13");
+    assert_eq!(lines[1], "1..4");
+    assert_eq!(lines[2], "ok 1 - suite_test.lox > addition");
+    assert_eq!(lines[3], "not ok 2 - suite_test.lox > failing assertion");
+}
+
+#[test]
+fn test_junit_report() {
+    let results = run_source("suite_test.lox", SUITE);
+    let report = render(&results, ReportFormat::JUnit);
+    assert!(report.starts_with("<?xml"));
+    assert!(report.contains(r#"<testsuites name="rlox" tests="4" failures="1">"#));
+    assert!(report.contains(r#"<testcase name="addition" classname="suite_test.lox""#));
+    assert!(
+        report.contains("<failure message=\"Runtime error: Assertion failed: base is not zero\"/>")
+    );
+}
+
+#[test]
+fn test_discover() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("nested")).unwrap();
+    fs::write(dir.path().join("a_test.lox"), "").unwrap();
+    fs::write(dir.path().join("nested/b_test.lox"), "").unwrap();
+    fs::write(dir.path().join("helper.lox"), "").unwrap();
+
+    let files = discover(dir.path()).unwrap();
+    let names: Vec<String> = files
+        .iter()
+        .map(|f| {
+            f.strip_prefix(dir.path())
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names, vec!["a_test.lox", "nested/b_test.lox"]);
+}
+
+#[test]
+fn test_output_is_captured() {
+    let source = r#"
+print "setup";
+
+test "prints" {
+    print "body";
+}
+
+test "fails" {
+    assert(false, "no");
+}
+"#;
+    let results = run_source("print_test.lox", source);
+    assert_eq!(results[0].output, "setup\nbody\n");
+    assert_eq!(results[1].output, "setup\n");
+
+    let summary = render(&results, ReportFormat::Summary);
+    assert!(!summary.contains("body"));
+    assert!(
+        summary.contains(
+            "FAIL print_test.lox > fails: Runtime error: Assertion failed: no\n    setup\n"
+        )
+    );
+    let report = render(&results, ReportFormat::JUnit);
+    assert!(report.contains("<system-out>setup&#10;body&#10;</system-out>"));
+}
+
+#[test]
+fn test_name_is_unescaped() {
+    let results = run_source("name_test.lox", r#"test "say \"hi\"\t" {}"#);
+    assert_eq!(results[0].name, "say \"hi\"\t");
+
+    let results = run_source("name_test.lox", r#"test "bad \q" {}"#);
+    assert_eq!(results[0].name, "<load>");
+}
+
+#[test]
+fn test_exit_fails_only_its_test() {
+    let source = r#"
+test "exits" {
+    exit(0);
+}
+
+test "still runs" {
+    assert(true);
+}
+"#;
+    let results = run_source("exit_test.lox", source);
+    assert_eq!(results[0].failure.as_deref(), Some("Exited with status 0."));
+    assert!(results[1].passed());
+}
@@ -0,0 +1,88 @@
+use std::{fs, io, process::Command};
+
+use rlox::{interpreter::Interpreter, parser::Parser, scanner::Scanner, trace::Tracer};
+use tempfile::tempdir;
+
+mod common;
+use common::Buffer;
+
+fn trace(source: &str, lines: Option<std::ops::RangeInclusive<usize>>) -> String {
+    let tokens = Scanner::new(source.to_owned()).scan_tokens().unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    let buffer = Buffer::default();
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(io::sink()));
+    interpreter.set_hook(Box::new(Tracer::new(Box::new(buffer.clone()), lines)));
+    let _ = interpreter.execute(&program);
+    buffer.take()
+}
+
+#[test]
+fn test_trace_statements_and_expressions() {
+    let source = "var a = 1;\n{\n  print a + 2;\n}\n";
+    assert_eq!(
+        trace(source, None),
+        "[line 1] [depth 0] stmt (var a = 1)
+[line 1] [depth 0] expr 1 => 1
+[line 2] [depth 0] stmt block
+[line 3] [depth 1] stmt (print (+ a 2))
+[line 3] [depth 1] expr a => 1
+[line 3] [depth 1] expr 2 => 2
+[line 3] [depth 1] expr (+ a 2) => 3
+"
+    );
+}
+
+#[test]
+fn test_trace_expression_lines() {
+    // Expressions spanning lines are traced at their own line.
+    let source = "var a = 1;\nprint a\n  + a;";
+    assert_eq!(
+        trace(source, Some(2..=3)),
+        "[line 2] [depth 0] stmt (print (+ a a))
+[line 2] [depth 0] expr a => 1
+[line 3] [depth 0] expr a => 1
+[line 3] [depth 0] expr (+ a a) => 2
+"
+    );
+}
+
+#[test]
+fn test_trace_line_range() {
+    let source = "var a = 1;\nvar b = 2;\nvar c = 3;\n";
+    assert_eq!(
+        trace(source, Some(2..=2)),
+        "[line 2] [depth 0] stmt (var b = 2)\n[line 2] [depth 0] expr 2 => 2\n"
+    );
+}
+
+#[test]
+fn test_trace_stops_at_error() {
+    let source = "print 1;\nprint -\"a\";\nprint 2;\n";
+    let trace = trace(source, None);
+    assert!(trace.ends_with("[line 2] [depth 0] expr \"a\" => \"a\"\n"));
+}
+
+#[test]
+fn test_trace_cli_output_file() {
+    let dir = tempdir().unwrap();
+    let script = dir.path().join("script.lox");
+    let log = dir.path().join("trace.log");
+    fs::write(&script, "var a = 1;\nprint a;\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .arg("--trace")
+        .arg(format!("--output={}", log.display()))
+        .arg("--lines=2-2")
+        .arg(&script)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1\n");
+    assert!(output.stderr.is_empty());
+    assert_eq!(
+        fs::read_to_string(log).unwrap(),
+        "[line 2] [depth 0] stmt (print a)\n[line 2] [depth 0] expr a => 1\n"
+    );
+}